@@ -0,0 +1,566 @@
+//! Module defining the checker and its configuration, independently of the cli.
+//!
+//! A [`Checker`] is configured through a [`CheckerBuilder`], which validates globs and regex once.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::path::PathBuf;
+#[cfg(feature = "check")]
+use std::sync::Arc;
+use std::thread::available_parallelism;
+use std::time::Duration;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+#[cfg(feature = "check")]
+use reqwest::blocking::{Client, ClientBuilder};
+
+use crate::files::file::File;
+use crate::files::links::accept::{Accept, Codes};
+use crate::files::links::extractor::{Extractor, Extractors, MarkdownExtractor};
+use crate::files::links::filter::Filter;
+use crate::files::links::link::Link;
+use crate::files::links::method::Method;
+use crate::files::links::policy::Policy;
+use crate::files::links::reason::Reason;
+#[cfg(feature = "check")]
+use crate::files::links::resolver::Resolver;
+#[cfg(feature = "check")]
+use crate::files::links::results::Results;
+use crate::files::Files;
+use crate::report::Format;
+#[cfg(feature = "check")]
+use crate::report::{Reporter, Silent};
+
+/// Represents the settings of a check
+pub struct CheckerConfig {
+    /// Paths to recursively search
+    pub path: Vec<String>,
+    /// Set of globs to validate files
+    pub glob: GlobSet,
+    /// Set of globs to exclude files
+    pub exclude: GlobSet,
+    /// Links to ignore checking
+    pub filter: Filter,
+    /// Hidden files and directories are processed
+    pub hidden: bool,
+    /// Extractors of links, per file type
+    pub extractors: Extractors,
+    /// Directory root-absolute local links are resolved from, they are ignored without it
+    pub root_dir: Option<PathBuf>,
+    /// Maximum number of links checked concurrently
+    pub jobs: usize,
+    /// Maximum number of requests per second to a single host
+    pub host_rps: Option<f64>,
+    /// Maximum number of requests in flight to a single host
+    pub host_jobs: Option<usize>,
+    /// Maximum number of retries of transient failures
+    pub retries: u32,
+    /// Delay before the first retry, doubled on every following retry
+    pub retry_backoff: Duration,
+    /// Which failure reasons make a link dead
+    pub policy: Policy,
+    /// Maximum duration to establish a connection
+    pub connect_timeout: Duration,
+    /// Maximum duration of a whole request
+    pub timeout: Duration,
+    /// HTTP method used to request links
+    pub method: Method,
+    /// Status codes making a link alive
+    pub accept: Accept,
+    /// File where checked links are cached between runs
+    pub cache: Option<PathBuf>,
+    /// Maximum age of cached links before they are checked again
+    pub cache_max_age: Duration,
+    /// Output format of the results
+    pub format: Format,
+}
+
+impl Default for CheckerConfig {
+    fn default() -> Self {
+        Self {
+            path: vec![String::from(".")],
+            glob: globs([String::from("**")].iter()).expect("default glob should be valid"),
+            exclude: GlobSet::empty(),
+            filter: Filter::default(),
+            hidden: false,
+            extractors: Extractors::default(),
+            root_dir: None,
+            jobs: default_jobs(),
+            host_rps: None,
+            host_jobs: None,
+            retries: 0,
+            retry_backoff: Duration::from_millis(500),
+            policy: Policy::default(),
+            connect_timeout: Duration::from_secs(10),
+            timeout: Duration::from_secs(30),
+            method: Method::Head,
+            accept: Accept::default(),
+            cache: None,
+            cache_max_age: Duration::from_secs(24 * 60 * 60),
+            format: Format::Text,
+        }
+    }
+}
+
+impl CheckerConfig {
+    /// Whether `link` is not checked, being ignored by the filter, neither remote nor local,
+    /// or root-absolute without a root directory.
+    pub fn ignores(&self, link: &Link) -> bool {
+        let checkable = link.is_remote()
+            || (link.is_local() && (self.root_dir.is_some() || !link.is_root_absolute()));
+
+        !checkable || self.filter.ignores(link)
+    }
+
+    /// Builds the HTTP client used to check links, according to the settings.
+    ///
+    /// Host names are looked up by the system resolver, raising typed errors so DNS failures can be told apart.
+    #[cfg(feature = "check")]
+    pub fn client(&self) -> Client {
+        let builder = reqwest::ClientBuilder::new().dns_resolver(Arc::new(Resolver::new()));
+
+        ClientBuilder::from(builder)
+            .connect_timeout(self.connect_timeout)
+            .timeout(self.timeout)
+            .build()
+            .expect("HTTP client should be built")
+    }
+}
+
+/// Represents an invalid setting given to a [`CheckerBuilder`]
+#[derive(Debug)]
+pub enum ConfigError {
+    /// A glob to select or exclude files is invalid
+    Glob(globset::Error),
+    /// A regex to ignore or include links is invalid
+    Regex(regex::Error),
+    /// A maximum number of requests per second is not a positive number, or too small
+    HostRps(f64),
+    /// A failure reason making a link dead or a warning does not exist
+    Reason(String),
+    /// A timeout, named by the value, is zero, making every request time out
+    Timeout(&'static str),
+}
+
+impl Display for ConfigError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ConfigError::Glob(e) => write!(f, "invalid glob: {}", e),
+            ConfigError::Regex(e) => write!(f, "invalid regex: {}", e),
+            ConfigError::HostRps(rps) => write!(
+                f,
+                "invalid requests per second: {}, should be a finite positive number not too close to zero",
+                rps
+            ),
+            ConfigError::Reason(name) => write!(
+                f,
+                "invalid failure reason: `{}`, expected one of {}",
+                name,
+                Reason::NAMES.join(", ")
+            ),
+            ConfigError::Timeout(name) => write!(f, "invalid {}: should be longer than zero", name),
+        }
+    }
+}
+
+impl Error for ConfigError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            ConfigError::Glob(e) => Some(e),
+            ConfigError::Regex(e) => Some(e),
+            ConfigError::HostRps(_) | ConfigError::Reason(_) | ConfigError::Timeout(_) => None,
+        }
+    }
+}
+
+impl From<globset::Error> for ConfigError {
+    fn from(e: globset::Error) -> Self {
+        ConfigError::Glob(e)
+    }
+}
+
+impl From<regex::Error> for ConfigError {
+    fn from(e: regex::Error) -> Self {
+        ConfigError::Regex(e)
+    }
+}
+
+/// Builds a [`Checker`], starting from the default settings
+#[derive(Default)]
+pub struct CheckerBuilder {
+    /// Settings not needing validation
+    config: CheckerConfig,
+    /// Paths to recursively search, the current directory if empty
+    path: Vec<String>,
+    /// Globs to validate files, every file if empty
+    glob: Vec<String>,
+    /// Globs to exclude files
+    exclude: Vec<String>,
+    /// Extractors with the glob of the files they apply to
+    extractors: Vec<(String, Box<dyn Extractor>)>,
+    /// Regex of links to ignore
+    ignore: Vec<String>,
+    /// Regex of links to check, others being ignored
+    include: Vec<String>,
+    /// Names of the failure reasons making a link dead
+    dead_on: Vec<String>,
+    /// Names of the failure reasons only generating a warning
+    warn_on: Vec<String>,
+    /// Status codes making a link alive
+    accept: Codes,
+    /// Status codes making a link to a specific host alive
+    accept_hosts: Vec<(String, Codes)>,
+}
+
+impl CheckerBuilder {
+    /// Adds a path to recursively search
+    pub fn path(mut self, path: impl Into<String>) -> Self {
+        self.path.push(path.into());
+        self
+    }
+
+    /// Adds a Unix-style glob to select files
+    pub fn glob(mut self, glob: impl Into<String>) -> Self {
+        self.glob.push(glob.into());
+        self
+    }
+
+    /// Adds a Unix-style glob to exclude files
+    pub fn exclude(mut self, glob: impl Into<String>) -> Self {
+        self.exclude.push(glob.into());
+        self
+    }
+
+    /// Whether hidden files and directories are processed
+    pub fn hidden(mut self, hidden: bool) -> Self {
+        self.config.hidden = hidden;
+        self
+    }
+
+    /// Extracts links of files matching `glob` with `extractor`, instead of the default one.
+    ///
+    /// Extractors added last take precedence.
+    pub fn extractor(mut self, glob: impl Into<String>, extractor: Box<dyn Extractor>) -> Self {
+        self.extractors.push((glob.into(), extractor));
+        self
+    }
+
+    /// Extracts URLs inside code spans and code blocks of Markdown files, they are skipped by default
+    pub fn markdown_code(mut self, code: bool) -> Self {
+        self.config.extractors = Extractors::with_markdown(MarkdownExtractor { code });
+        self
+    }
+
+    /// Resolves root-absolute local links, such as `/docs/setup.html`, from the directory at `path`
+    pub fn root_dir(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.root_dir = Some(path.into());
+        self
+    }
+
+    /// Adds a regex of links to ignore
+    pub fn ignore(mut self, regex: impl Into<String>) -> Self {
+        self.ignore.push(regex.into());
+        self
+    }
+
+    /// Adds a regex of links to check, others being ignored
+    pub fn include(mut self, regex: impl Into<String>) -> Self {
+        self.include.push(regex.into());
+        self
+    }
+
+    /// Sets the maximum number of links checked concurrently
+    pub fn jobs(mut self, jobs: usize) -> Self {
+        self.config.jobs = jobs;
+        self
+    }
+
+    /// Limits the number of requests per second to a single host
+    pub fn host_rps(mut self, rps: f64) -> Self {
+        self.config.host_rps = Some(rps);
+        self
+    }
+
+    /// Limits the number of requests in flight to a single host
+    pub fn host_jobs(mut self, jobs: usize) -> Self {
+        self.config.host_jobs = Some(jobs);
+        self
+    }
+
+    /// Sets the maximum number of retries of transient failures
+    pub fn retries(mut self, retries: u32) -> Self {
+        self.config.retries = retries;
+        self
+    }
+
+    /// Sets the delay before the first retry, doubled on every following retry
+    pub fn retry_backoff(mut self, backoff: Duration) -> Self {
+        self.config.retry_backoff = backoff;
+        self
+    }
+
+    /// Makes failures with the reason named `reason` dead links
+    pub fn dead_on(mut self, reason: impl Into<String>) -> Self {
+        self.dead_on.push(reason.into());
+        self
+    }
+
+    /// Makes failures with the reason named `reason` warnings only
+    pub fn warn_on(mut self, reason: impl Into<String>) -> Self {
+        self.warn_on.push(reason.into());
+        self
+    }
+
+    /// Sets the maximum duration to establish a connection, longer than zero
+    pub fn connect_timeout(mut self, timeout: Duration) -> Self {
+        self.config.connect_timeout = timeout;
+        self
+    }
+
+    /// Sets the maximum duration of a whole request, longer than zero
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.config.timeout = timeout;
+        self
+    }
+
+    /// Sets the HTTP method used to request links
+    pub fn method(mut self, method: Method) -> Self {
+        self.config.method = method;
+        self
+    }
+
+    /// Sets the status codes making a link alive
+    pub fn accept(mut self, codes: Codes) -> Self {
+        self.accept = codes;
+        self
+    }
+
+    /// Sets the status codes making a link to `host` and its subdomains alive
+    pub fn accept_host(mut self, host: impl Into<String>, codes: Codes) -> Self {
+        self.accept_hosts.push((host.into().to_lowercase(), codes));
+        self
+    }
+
+    /// Caches checked links between runs in the file at `path`
+    pub fn cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.cache = Some(path.into());
+        self
+    }
+
+    /// Sets the maximum age of cached links before they are checked again
+    pub fn cache_max_age(mut self, max_age: Duration) -> Self {
+        self.config.cache_max_age = max_age;
+        self
+    }
+
+    /// Sets the output format of the results
+    pub fn format(mut self, format: Format) -> Self {
+        self.config.format = format;
+        self
+    }
+
+    /// Validates the settings and builds the checker
+    pub fn build(self) -> Result<Checker, ConfigError> {
+        let mut config = self.config;
+
+        if !self.path.is_empty() {
+            config.path = self.path;
+        }
+        if !self.glob.is_empty() {
+            config.glob = globs(self.glob.iter())?;
+        }
+        config.exclude = globs(self.exclude.iter())?;
+        for (glob, extractor) in self.extractors {
+            config.extractors.register(&glob, extractor)?;
+        }
+        if let Some(rps) = config.host_rps {
+            let valid =
+                rps.is_finite() && rps > 0.0 && Duration::try_from_secs_f64(1.0 / rps).is_ok();
+
+            if !valid {
+                return Err(ConfigError::HostRps(rps));
+            }
+        }
+        if config.connect_timeout.is_zero() {
+            return Err(ConfigError::Timeout("connect timeout"));
+        }
+        if config.timeout.is_zero() {
+            return Err(ConfigError::Timeout("timeout"));
+        }
+        config.filter = Filter::new(self.ignore.iter(), self.include.iter())?;
+        if let Some(name) = self
+            .dead_on
+            .iter()
+            .chain(&self.warn_on)
+            .find(|name| !Reason::NAMES.contains(&name.as_str()))
+        {
+            return Err(ConfigError::Reason(name.clone()));
+        }
+        config.policy = Policy::new(self.dead_on.iter(), self.warn_on.iter());
+        config.accept = Accept::new(self.accept, self.accept_hosts.into_iter());
+
+        Ok(Checker::new(config))
+    }
+}
+
+/// Represents a configured dead links checker
+#[derive(Default)]
+pub struct Checker {
+    /// Settings of the checks
+    config: CheckerConfig,
+}
+
+impl Checker {
+    /// Creates a new checker with the given settings
+    pub fn new(config: CheckerConfig) -> Self {
+        Self { config }
+    }
+
+    /// Starts building a checker from the default settings
+    pub fn builder() -> CheckerBuilder {
+        CheckerBuilder::default()
+    }
+
+    /// Settings of the checks
+    pub fn config(&self) -> &CheckerConfig {
+        &self.config
+    }
+
+    /// Finds the files to check
+    pub fn files(&self) -> impl Iterator<Item = File> + '_ {
+        Files::find(&self.config)
+    }
+
+    /// Checks every link and returns the results, without printing anything
+    #[cfg(feature = "check")]
+    pub fn results(&self) -> Results {
+        self.check(&mut Silent)
+    }
+
+    /// Checks every link, streaming progress to `reporter`, and returns the results
+    #[cfg(feature = "check")]
+    pub fn check(&self, reporter: &mut dyn Reporter) -> Results {
+        Files::with_client(self.config.client()).check(&self.config, reporter)
+    }
+}
+
+/// Default number of concurrent checks, one per available CPU
+fn default_jobs() -> usize {
+    available_parallelism().map(usize::from).unwrap_or(1)
+}
+
+/// Builds a set of globs to test the files against
+fn globs<'a>(globs: impl Iterator<Item = &'a String>) -> Result<GlobSet, globset::Error> {
+    let mut builder = GlobSetBuilder::new();
+
+    for glob in globs {
+        builder.add(
+            GlobBuilder::new(glob.as_str())
+                .literal_separator(true)
+                .build()?,
+        );
+    }
+
+    builder.build()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use crate::files::links::link::Link;
+
+    use super::*;
+
+    #[test]
+    fn defaults() {
+        let checker = Checker::builder().build().unwrap();
+
+        assert_eq!(checker.config().path, ["."]);
+        assert!(checker.config().glob.is_match(Path::new("docs/README.md")));
+        assert!(!checker
+            .config()
+            .exclude
+            .is_match(Path::new("docs/README.md")));
+    }
+
+    #[test]
+    fn send_and_sync() {
+        fn assert_send_sync<T: Send + Sync>() {}
+
+        assert_send_sync::<Checker>();
+        assert_send_sync::<CheckerBuilder>();
+    }
+
+    #[test]
+    fn no_path() {
+        let checker = Checker::new(CheckerConfig {
+            path: Vec::new(),
+            ..CheckerConfig::default()
+        });
+
+        assert!(checker.files().next().is_some());
+    }
+
+    #[test]
+    fn build() {
+        let checker = Checker::builder()
+            .path("docs")
+            .glob("**/*.md")
+            .exclude("**/CHANGELOG.md")
+            .ignore("^mailto:")
+            .retries(3)
+            .method(Method::Get)
+            .build()
+            .unwrap();
+        let config = checker.config();
+
+        assert_eq!(config.path, ["docs"]);
+        assert!(config.glob.is_match(Path::new("docs/README.md")));
+        assert!(!config.glob.is_match(Path::new("docs/index.html")));
+        assert!(config.exclude.is_match(Path::new("docs/CHANGELOG.md")));
+        assert!(config
+            .filter
+            .ignores(&Link::new(&"mailto:someone@example.com".to_string())));
+        assert_eq!(config.retries, 3);
+        assert_eq!(config.method, Method::Get);
+    }
+
+    #[test]
+    fn invalid() {
+        assert!(matches!(
+            Checker::builder().glob("a/{b").build(),
+            Err(ConfigError::Glob(_))
+        ));
+        assert!(matches!(
+            Checker::builder().ignore("(").build(),
+            Err(ConfigError::Regex(_))
+        ));
+
+        assert!(matches!(
+            Checker::builder().dead_on("dsn").build(),
+            Err(ConfigError::Reason(name)) if name == "dsn"
+        ));
+        assert!(Checker::builder()
+            .warn_on("status")
+            .dead_on("dns")
+            .build()
+            .is_ok());
+
+        assert!(matches!(
+            Checker::builder().timeout(Duration::ZERO).build(),
+            Err(ConfigError::Timeout("timeout"))
+        ));
+        assert!(matches!(
+            Checker::builder().connect_timeout(Duration::ZERO).build(),
+            Err(ConfigError::Timeout("connect timeout"))
+        ));
+
+        for rps in [0.0, -1.0, f64::NAN, f64::INFINITY, 1e-20] {
+            assert!(matches!(
+                Checker::builder().host_rps(rps).build(),
+                Err(ConfigError::HostRps(_))
+            ));
+        }
+    }
+}
@@ -1,108 +1,245 @@
 //! This module contains cli arguments handling
 
-use clap::{arg, command, ArgMatches};
-use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+use std::fmt::Display;
+use std::path::PathBuf;
+use std::time::Duration;
 
-use crate::files::links::link::Link;
+use clap::builder::PossibleValuesParser;
+use clap::error::ErrorKind;
+use clap::{arg, command, value_parser};
+
+use crate::checker::{Checker, CheckerConfig};
+use crate::files::links::accept::{Accept, Codes};
+use crate::files::links::extractor::{Extractor, Extractors, MarkdownExtractor};
+use crate::files::links::filter::Filter;
+use crate::files::links::method::Method;
+use crate::files::links::reason::Reason;
+use crate::report::Format;
 
 /// Represents the arguments given to the cli
+#[derive(Default)]
 pub struct Cli {
-    /// Paths to recursively search
-    pub path: Vec<String>,
-    /// Set of globs to validate files
-    pub glob: GlobSet,
-    /// Set of globs to exclude files
-    pub exclude: GlobSet,
-    /// Links to ignore checking
-    pub ignore: Vec<Link>,
-    /// Hidden files and directories are processed
-    pub hidden: bool,
+    /// Checker configured by the arguments
+    pub checker: Checker,
     /// Only list files that would be processed
     pub list: bool,
     /// Only list links that would be checked
     pub dry: bool,
 }
 
-impl Default for Cli {
-    fn default() -> Self {
-        Self {
-            path: vec![String::from(".")],
-            glob: Self::globs(vec![String::from("**")].iter()),
-            exclude: GlobSet::default(),
-            ignore: Vec::new(),
-            hidden: false,
-            list: false,
-            dry: false,
-        }
-    }
-}
-
 impl Cli {
     /// Creates a new cli arguments wrapper ready to use.
+    ///
+    /// Arguments are translated into a [`Checker`], exiting with an error if they are invalid.
+    /// Missing arguments keep the default settings of the checker.
     pub fn build() -> Self {
-        let matches: ArgMatches = command!()
-            .arg(arg!([path]... "Path to look for files").default_value("."))
-            .arg(arg!(-g --glob <glob>... "Unix-style glob to filter files").default_value("**"))
+        let defaults = CheckerConfig::default();
+        let mut command = command!()
+            .arg(arg!([path]...).help(help("Path to look for files", defaults.path.join(" "))))
+            .arg(arg!(-g --glob <glob>... "Unix-style glob to filter files [default: every file]"))
             .arg(arg!(-e --exclude <glob>... "Unix-style glob to exclude from selection"))
             .arg(arg!(--hidden "Includes hidden files and directories"))
-            .arg(arg!(-i --ignore <url>... "URL to ignore"))
+            .arg(
+                arg!(--extractor <rule>... "Extractor of the files matching a glob, e.g. `*.txt=markdown` (plain, html, markdown)")
+                    .value_parser(Extractors::parse_rule),
+            )
+            .arg(arg!(--"markdown-code" "Extracts URLs inside code spans and code blocks of Markdown files"))
+            .arg(arg!(-i --ignore <regex>... "Regex of URLs to ignore").value_parser(Filter::validate))
+            .arg(
+                arg!(--include <regex>... "Regex of URLs to check, others are ignored")
+                    .value_parser(Filter::validate),
+            )
+            .arg(
+                arg!(--"root-dir" <dir> "Directory root-absolute local links such as `/docs/setup.html` are resolved from")
+                    .value_parser(value_parser!(PathBuf)),
+            )
+            .arg(
+                arg!(-j --jobs <n> "Maximum number of links checked concurrently [default: number of CPUs]")
+                    .value_parser(value_parser!(usize)),
+            )
+            .arg(
+                arg!(--"host-rps" <n> "Maximum number of requests per second to a single host")
+                    .value_parser(value_parser!(f64)),
+            )
+            .arg(
+                arg!(--"host-jobs" <n> "Maximum number of requests in flight to a single host")
+                    .value_parser(value_parser!(usize)),
+            )
+            .arg(
+                arg!(--retries <n>)
+                    .help(help("Number of retries of network errors, 429 and 5xx responses", defaults.retries))
+                    .value_parser(value_parser!(u32)),
+            )
+            .arg(
+                arg!(--"retry-backoff" <ms>)
+                    .help(help(
+                        "Delay before the first retry in milliseconds, doubled on every retry",
+                        defaults.retry_backoff.as_millis(),
+                    ))
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                arg!(--"connect-timeout" <s>)
+                    .help(help(
+                        "Maximum duration to establish a connection in seconds",
+                        defaults.connect_timeout.as_secs(),
+                    ))
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                arg!(--timeout <s>)
+                    .help(help("Maximum duration of a whole request in seconds", defaults.timeout.as_secs()))
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                arg!(-m --method <method>)
+                    .help(help(
+                        "HTTP method, `head` falls back to `get` when unsupported by the server",
+                        defaults.method,
+                    ))
+                    .value_parser(PossibleValuesParser::new(Method::NAMES)),
+            )
+            .arg(
+                arg!(--accept <codes>)
+                    .help(help(
+                        "Status codes and ranges making a link alive, e.g. `200..=299,401,403`",
+                        Codes::default(),
+                    ))
+                    .value_parser(Codes::parse),
+            )
+            .arg(
+                arg!(--"accept-host" <rule>... "Status codes making a link to a host alive, e.g. `linkedin.com=200..=299,999`")
+                    .value_parser(Accept::parse_host),
+            )
+            .arg(
+                arg!(--cache <file> "File where checked links are cached between runs")
+                    .value_parser(value_parser!(PathBuf)),
+            )
+            .arg(
+                arg!(--"cache-max-age" <s>)
+                    .help(help(
+                        "Maximum age of cached links in seconds before they are checked again",
+                        defaults.cache_max_age.as_secs(),
+                    ))
+                    .value_parser(value_parser!(u64)),
+            )
+            .arg(
+                arg!(--"dead-on" <reason>... "Failure reasons making a link dead [default: status, missing]")
+                    .value_parser(PossibleValuesParser::new(Reason::NAMES)),
+            )
+            .arg(
+                arg!(--"warn-on" <reason>... "Failure reasons only generating a warning")
+                    .value_parser(PossibleValuesParser::new(Reason::NAMES)),
+            )
+            .arg(
+                arg!(-f --format <format> "Output format of the results [default: github inside GitHub Actions, text otherwise]")
+                    .value_parser(PossibleValuesParser::new(Format::NAMES)),
+            )
             .arg(arg!(--list "List searched files and exits"))
-            .arg(arg!(--dry "Extract and print URLs that should be requested but don't send requests"))
-            .get_matches();
+            .arg(arg!(--dry "Extract and print URLs that should be requested but don't send requests"));
+        let matches = command.get_matches_mut();
 
-        Self {
-            path: matches
-                .get_many::<String>("path")
-                .expect("path arguments should be valid")
-                .map(String::from)
-                .collect(),
-            glob: Cli::globs(
+        let markdown = MarkdownExtractor {
+            code: matches.get_flag("markdown-code"),
+        };
+        let mut builder = Checker::builder()
+            .hidden(matches.get_flag("hidden"))
+            .markdown_code(markdown.code)
+            .format(
                 matches
-                    .get_many::<String>("glob")
-                    .expect("glob arguments should be valid"),
-            ),
-            exclude: Cli::exclude_globs(matches.get_many::<String>("exclude").unwrap_or_default()),
-            ignore: matches
-                .get_many::<String>("ignore")
-                .unwrap_or_default()
-                .map(Link::new)
-                .collect(),
-            hidden: matches.get_flag("hidden"),
-            list: matches.get_flag("list"),
-            dry: matches.get_flag("dry"),
-        }
-    }
-
-    /// Build the set of globs to test the files against
-    fn globs<'a>(globs: impl Iterator<Item = &'a String>) -> GlobSet {
-        let mut builder = GlobSetBuilder::new();
-
-        for glob in globs {
-            builder.add(
-                GlobBuilder::new(glob.as_str())
-                    .literal_separator(true)
-                    .build()
-                    .expect("Glob pattern should be correct"),
+                    .get_one::<String>("format")
+                    .map(|format| {
+                        Format::from_name(format).expect("format argument should be valid")
+                    })
+                    .unwrap_or_else(Format::detect),
             );
-        }
 
-        builder.build().expect("Glob patterns should be correct")
-    }
-
-    /// Build the set of globs to exclude files
-    fn exclude_globs<'a>(globs: impl Iterator<Item = &'a String>) -> GlobSet {
-        let mut exclude_builder = GlobSetBuilder::new();
+        for path in matches.get_many::<String>("path").unwrap_or_default() {
+            builder = builder.path(path);
+        }
+        for glob in matches.get_many::<String>("glob").unwrap_or_default() {
+            builder = builder.glob(glob);
+        }
+        for glob in matches.get_many::<String>("exclude").unwrap_or_default() {
+            builder = builder.exclude(glob);
+        }
+        for (glob, name) in matches
+            .get_many::<(String, String)>("extractor")
+            .unwrap_or_default()
+        {
+            let extractor: Box<dyn Extractor> = match name.as_str() {
+                "markdown" => Box::new(markdown),
+                name => Extractors::from_name(name).expect("extractor argument should be valid"),
+            };
+            builder = builder.extractor(glob, extractor);
+        }
+        for regex in matches.get_many::<String>("ignore").unwrap_or_default() {
+            builder = builder.ignore(regex);
+        }
+        for regex in matches.get_many::<String>("include").unwrap_or_default() {
+            builder = builder.include(regex);
+        }
+        for reason in matches.get_many::<String>("dead-on").unwrap_or_default() {
+            builder = builder.dead_on(reason);
+        }
+        for reason in matches.get_many::<String>("warn-on").unwrap_or_default() {
+            builder = builder.warn_on(reason);
+        }
+        for (host, codes) in matches
+            .get_many::<(String, Codes)>("accept-host")
+            .unwrap_or_default()
+        {
+            builder = builder.accept_host(host, codes.clone());
+        }
+        if let Some(dir) = matches.get_one::<PathBuf>("root-dir") {
+            builder = builder.root_dir(dir);
+        }
+        if let Some(jobs) = matches.get_one::<usize>("jobs") {
+            builder = builder.jobs(*jobs);
+        }
+        if let Some(rps) = matches.get_one::<f64>("host-rps") {
+            builder = builder.host_rps(*rps);
+        }
+        if let Some(jobs) = matches.get_one::<usize>("host-jobs") {
+            builder = builder.host_jobs(*jobs);
+        }
+        if let Some(retries) = matches.get_one::<u32>("retries") {
+            builder = builder.retries(*retries);
+        }
+        if let Some(ms) = matches.get_one::<u64>("retry-backoff") {
+            builder = builder.retry_backoff(Duration::from_millis(*ms));
+        }
+        if let Some(s) = matches.get_one::<u64>("connect-timeout") {
+            builder = builder.connect_timeout(Duration::from_secs(*s));
+        }
+        if let Some(s) = matches.get_one::<u64>("timeout") {
+            builder = builder.timeout(Duration::from_secs(*s));
+        }
+        if let Some(method) = matches.get_one::<String>("method") {
+            builder =
+                builder.method(Method::from_name(method).expect("method argument should be valid"));
+        }
+        if let Some(codes) = matches.get_one::<Codes>("accept") {
+            builder = builder.accept(codes.clone());
+        }
+        if let Some(path) = matches.get_one::<PathBuf>("cache") {
+            builder = builder.cache(path);
+        }
+        if let Some(s) = matches.get_one::<u64>("cache-max-age") {
+            builder = builder.cache_max_age(Duration::from_secs(*s));
+        }
 
-        for glob in globs {
-            exclude_builder.add(
-                GlobBuilder::new(glob.as_str())
-                    .literal_separator(true)
-                    .build()
-                    .expect("Exclude glob pattern should be correct"),
-            );
+        Self {
+            checker: builder
+                .build()
+                .unwrap_or_else(|e| command.error(ErrorKind::ValueValidation, e).exit()),
+            list: matches.get_flag("list"),
+            dry: matches.get_flag("dry"),
         }
-        exclude_builder
-            .build()
-            .expect("Exclude glob patterns should be correct")
     }
 }
+
+/// Help of an argument, followed by its `default` value
+fn help(help: &str, default: impl Display) -> String {
+    format!("{} [default: {}]", help, default)
+}
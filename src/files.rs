@@ -1,20 +1,49 @@
 //! Module for anything file related,
 //! that is list of files, file, list of links ...
 
+#[cfg(feature = "check")]
+use std::collections::{HashMap, HashSet};
+#[cfg(feature = "check")]
+use std::io;
+#[cfg(feature = "check")]
+use std::path::Path;
+#[cfg(feature = "check")]
+use std::time::Duration;
+
 use ignore::WalkBuilder;
+#[cfg(feature = "check")]
+use reqwest::blocking::Client;
 
-use crate::cli::Cli;
+use crate::checker::CheckerConfig;
 use crate::files::file::File;
+#[cfg(feature = "check")]
 use crate::files::links::cache::Cache;
+#[cfg(feature = "check")]
+use crate::files::links::limiter::Limiter;
+#[cfg(feature = "check")]
+use crate::files::links::link::{Link, LinkStatus};
+#[cfg(feature = "check")]
+use crate::files::links::location::Location;
+#[cfg(feature = "check")]
+use crate::files::links::pool::Pool;
+#[cfg(feature = "check")]
 use crate::files::links::results::Results;
+#[cfg(feature = "check")]
+use crate::files::links::retry::Retry;
+#[cfg(feature = "check")]
+use crate::report::Reporter;
 
 pub mod file;
 pub mod links;
 
-/// Represents a list of files with a link cache.
+/// Represents a list of files with a link cache and an HTTP client.
 pub struct Files {
     /// Checked links cache
+    #[cfg(feature = "check")]
     cache: Cache,
+    /// HTTP client shared by every check, allowing connection reuse
+    #[cfg(feature = "check")]
+    client: Client,
 }
 
 impl Default for Files {
@@ -27,44 +56,230 @@ impl Files {
     /// Creates a new empty list of files
     pub fn new() -> Self {
         Self {
+            #[cfg(feature = "check")]
             cache: Cache::new(),
+            #[cfg(feature = "check")]
+            client: Client::new(),
         }
     }
 
-    /// Find files matching the globs and the cli arguments
-    pub fn find<'a, 'b>(cli: &'a Cli) -> impl Iterator<Item = File> + 'b
+    /// Find files matching the paths and globs of the configuration.
+    ///
+    /// Without any path, the current directory is searched.
+    pub fn find<'a, 'b>(config: &'a CheckerConfig) -> impl Iterator<Item = File> + 'b
     where
         'a: 'b,
     {
-        let mut builder = WalkBuilder::new(cli.path.first().unwrap());
+        let mut paths = config.path.iter().map(String::as_str);
+        let mut builder = WalkBuilder::new(paths.next().unwrap_or("."));
 
-        for path in &cli.path[1..] {
+        for path in paths {
             builder.add(path);
         }
 
         builder
             .standard_filters(false)
-            .hidden(!cli.hidden)
+            .hidden(!config.hidden)
             .build()
-            .into_iter()
             .filter_map(Result::ok)
             .filter(|x| x.metadata().unwrap().is_file())
-            .filter(|x| cli.glob.is_match(x.path()))
-            .filter(|x| !cli.exclude.is_match(x.path()))
+            .filter(|x| config.glob.is_match(x.path()))
+            .filter(|x| !config.exclude.is_match(x.path()))
             .map(|x| File::new(x.path()))
     }
+}
+
+#[cfg(feature = "check")]
+impl Files {
+    /// Creates a new empty list of files, checking links with `client`
+    pub fn with_client(client: Client) -> Self {
+        Self {
+            cache: Cache::new(),
+            client,
+        }
+    }
+
+    /// Loads the cache file at `path`, replacing the current cache.
+    ///
+    /// A missing cache file leaves an empty cache.
+    pub fn load_cache(&mut self, path: &Path, max_age: Duration) -> io::Result<()> {
+        self.cache = match Cache::load(path, max_age) {
+            Ok(cache) => cache,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => Cache::new(),
+            Err(e) => return Err(e),
+        };
+
+        Ok(())
+    }
+
+    /// Saves the current cache to the file at `path`.
+    pub fn save_cache(&self, path: &Path) -> io::Result<()> {
+        self.cache.save(path)
+    }
 
     /// Check every file that were matched by [`Files::find`].
     ///
-    /// Results are printed by file.
+    /// Unique links of every file are gathered first, then checked concurrently.
+    /// If configured, the cache is loaded before and saved after checking.
+    /// Every requested link is streamed to `reporter` as soon as it is checked,
+    /// and every file as soon as all its links are, as well as cache issues.
     /// Returns the merged results of every files.
-    pub fn check(&mut self, cli: &Cli) -> Results {
+    pub fn check(&mut self, config: &CheckerConfig, reporter: &mut dyn Reporter) -> Results {
+        if let Some(path) = &config.cache {
+            if let Err(e) = self.load_cache(path, config.cache_max_age) {
+                reporter.warning(&format!("Could not load cache: {}", e));
+            }
+        }
+
+        let files: Vec<(File, Vec<(Link, Location)>)> = Files::find(config)
+            .map(|file| {
+                let links = file.links(&config.extractors);
+                (file, links)
+            })
+            .collect();
+
+        let limiter = Limiter::new(config.host_rps, config.host_jobs);
+        let retry = Retry::new(config.retries, config.retry_backoff);
+        let pool = Pool::new(
+            config.jobs,
+            limiter,
+            retry,
+            config.method,
+            config.accept.clone(),
+        );
+        let unchecked = self.unchecked(
+            config,
+            files
+                .iter()
+                .flat_map(|(_, links)| links.iter().map(|(link, _)| link)),
+        );
+        let pending: HashSet<&Link> = unchecked.iter().collect();
+
+        let cache = &mut self.cache;
+        let client = &self.client;
+        let mut checked = HashMap::new();
         let mut results = Results::new();
+        let mut files = files.into_iter().peekable();
+
+        // Checks and reports, in order, the files whose links are all checked
+        let mut report_files = |checked: &HashMap<Link, LinkStatus>,
+                                reporter: &mut dyn Reporter| {
+            while let Some((file, links)) = files.next_if(|(_, links)| {
+                links
+                    .iter()
+                    .all(|(link, _)| !pending.contains(link) || checked.contains_key(link))
+            }) {
+                let file_results = file.check(&links, config, cache, client, checked);
+                reporter.file(&file, &file_results);
+                results.merge(file_results);
+            }
+        };
+
+        report_files(&checked, reporter);
+        pool.check(client, &unchecked, &mut |link, status| {
+            reporter.link(link, &config.policy.apply(status.clone()));
+            checked.insert(link.clone(), status.clone());
+            report_files(&checked, reporter);
+        });
 
-        for file in Files::find(cli) {
-            results.merge(file.check(cli, &mut self.cache));
+        if let Some(path) = &config.cache {
+            if let Err(e) = self.save_cache(path) {
+                reporter.warning(&format!("Could not save cache: {}", e));
+            }
         }
 
+        reporter.finish(&results);
+
         results
     }
+
+    /// Lists unique links that need to be requested.
+    ///
+    /// Local links, links ignored by the configuration or already in cache are left out.
+    /// The order of first appearance is kept.
+    fn unchecked<'a>(
+        &self,
+        config: &CheckerConfig,
+        links: impl Iterator<Item = &'a Link>,
+    ) -> Vec<Link> {
+        let mut seen = HashSet::new();
+
+        links
+            .filter(|link| link.is_remote())
+            .filter(|link| !config.ignores(link) && !self.cache.contains(link))
+            .filter(|link| seen.insert(*link))
+            .cloned()
+            .collect()
+    }
+}
+
+#[cfg(all(test, feature = "check"))]
+mod tests {
+    use std::fs::{create_dir_all, write};
+    use std::thread::sleep;
+
+    use crate::checker::Checker;
+    use crate::files::links::server::Server;
+
+    use super::*;
+
+    /// Reporter recording the links and files it is given, in order
+    #[derive(Default)]
+    struct Recorder {
+        /// Reported links and files
+        events: Vec<String>,
+    }
+
+    impl Reporter for Recorder {
+        fn link(&mut self, link: &Link, _status: &LinkStatus) {
+            self.events.push(link.to_string());
+        }
+
+        fn file(&mut self, file: &File, _results: &Results) {
+            self.events.push(format!("{:?}", file));
+        }
+    }
+
+    #[test]
+    fn files_reported_in_walk_order() {
+        let server = Server::start(|request| {
+            if request.contains("/slow") {
+                sleep(Duration::from_millis(300));
+            }
+
+            String::from("200 OK")
+        });
+        let dir = std::env::temp_dir().join("deadlinkrs-files-walk-order");
+        create_dir_all(&dir).unwrap();
+        let mut paths = [dir.join("a.md"), dir.join("b.md")];
+        for path in &paths {
+            write(path, "").unwrap();
+        }
+
+        let checker = Checker::builder()
+            .path(dir.display().to_string())
+            .jobs(2)
+            .build()
+            .unwrap();
+        if checker.files().next() != Some(File::new(&paths[0])) {
+            paths.reverse();
+        }
+
+        // The first file walked holds the link answering last
+        write(&paths[0], server.url("/slow")).unwrap();
+        write(&paths[1], server.url("/fast")).unwrap();
+
+        let mut recorder = Recorder::default();
+        checker.check(&mut recorder);
+
+        assert_eq!(
+            recorder.events,
+            [
+                server.url("/fast"),
+                server.url("/slow"),
+                format!("{:?}", File::new(&paths[0])),
+                format!("{:?}", File::new(&paths[1]))
+            ]
+        );
+    }
 }
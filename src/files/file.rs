@@ -1,12 +1,25 @@
 //! The file module groups everything related to a single file
 
+#[cfg(feature = "check")]
+use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
 use std::path::{Path, PathBuf};
 
+#[cfg(feature = "cli")]
 use ansi_term::Style;
+#[cfg(feature = "check")]
+use reqwest::blocking::Client;
 
-use crate::cli::Cli;
+#[cfg(any(feature = "check", feature = "cli"))]
+use crate::checker::CheckerConfig;
+#[cfg(feature = "check")]
 use crate::files::links::cache::Cache;
+use crate::files::links::extractor::Extractors;
+use crate::files::links::link::Link;
+#[cfg(feature = "check")]
+use crate::files::links::link::LinkStatus;
+use crate::files::links::location::Location;
+#[cfg(feature = "check")]
 use crate::files::links::results::Results;
 
 use super::links::Links;
@@ -26,35 +39,45 @@ impl File {
         }
     }
 
-    /// Check all the links in this file (if there are).
+    /// Finds the links in this file, with their location.
     ///
-    /// Then print and return the results.
-    pub fn check(&self, cli: &Cli, cache: &mut Cache) -> Results {
-        let results = Links::check(&self.path, cli, cache);
-
-        println!("{}{}", self, results);
+    /// An unreadable file has no links.
+    pub fn links(&self, extractors: &Extractors) -> Vec<(Link, Location)> {
+        Links::find(&self.path, extractors).unwrap_or_default()
+    }
 
-        results
+    /// Check the `links` of this file (if there are) with `client`, reusing already `checked` links.
+    #[cfg(feature = "check")]
+    pub fn check(
+        &self,
+        links: &[(Link, Location)],
+        config: &CheckerConfig,
+        cache: &mut Cache,
+        client: &Client,
+        checked: &HashMap<Link, LinkStatus>,
+    ) -> Results {
+        Links::check(&self.path, links, config, cache, client, checked)
     }
 
     /// Prints the links found in this file
-    pub fn print_links(&self, cli: &Cli) {
-        for link in Links::find(&self.path).unwrap_or_default() {
-            if !cli.ignore.contains(&link) {
-                println!("\t{}", link);
+    #[cfg(feature = "cli")]
+    pub fn print_links(&self, config: &CheckerConfig) {
+        for (link, location) in self.links(&config.extractors) {
+            if !config.ignores(&link) {
+                println!("\t{} {}", location.paint(), link.paint());
             }
         }
     }
+
+    /// Formats the path of this file for the terminal
+    #[cfg(feature = "cli")]
+    pub fn paint(&self) -> String {
+        Style::new().dimmed().paint(self.to_string()).to_string()
+    }
 }
 
 impl Display for File {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(
-            f,
-            "{}",
-            Style::new()
-                .dimmed()
-                .paint(&self.path.display().to_string())
-        )
+        write!(f, "{}", self.path.display())
     }
 }
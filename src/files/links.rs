@@ -1,69 +1,120 @@
 //! Module for anything link related
 
+#[cfg(feature = "check")]
+use std::collections::HashMap;
 use std::fs::read_to_string;
 use std::io;
+#[cfg(feature = "check")]
+use std::path::Path;
 use std::path::PathBuf;
 
-use regex::Regex;
+#[cfg(feature = "check")]
+use reqwest::blocking::Client;
 
 use link::Link;
+#[cfg(feature = "check")]
 use link::LinkStatus;
 
-use crate::cli::Cli;
+#[cfg(feature = "check")]
+use crate::checker::CheckerConfig;
+#[cfg(feature = "check")]
 use crate::files::links::cache::Cache;
+use crate::files::links::extractor::Extractors;
+use crate::files::links::location::{Location, Locator};
+#[cfg(feature = "check")]
 use crate::files::links::results::Results;
 
+pub mod accept;
+#[cfg(feature = "check")]
 pub mod cache;
+pub mod extractor;
+pub mod filter;
+pub mod html;
+#[cfg(feature = "check")]
+pub mod limiter;
 pub mod link;
+pub mod location;
+pub mod markdown;
+pub mod method;
+pub mod plain;
+pub mod policy;
+#[cfg(feature = "check")]
+pub mod pool;
+pub mod reason;
+#[cfg(feature = "check")]
+pub mod resolver;
 pub mod results;
+#[cfg(feature = "check")]
+pub mod retry;
+#[cfg(all(test, feature = "check"))]
+pub mod server;
 
 /// A list of links that can be tested
 pub struct Links;
 
 impl Links {
-    /// Finds the links in the file at `path`.
+    /// Finds the links in the file at `path`, with their location.
     ///
-    /// Uses a regex to do so.
-    pub fn find(path: &PathBuf) -> io::Result<Vec<Link>> {
-        // TODO: define some sort of constant
-        let regex: Regex = Regex::new(
-            "https?://(?:[[:alnum:]]+\\.)?[[:alnum:]]+\\.[[:alpha:]]{2,3}/?(?:[[:alnum:]]|[-$_.+!*/&?%=@,:])*",
-        )
-            .expect("Valid regex");
-
+    /// Links are extracted by the extractor registered for the file in `extractors`.
+    pub fn find(path: &PathBuf, extractors: &Extractors) -> io::Result<Vec<(Link, Location)>> {
         let content = read_to_string(path)?;
-
-        Ok(regex.find_iter(&content).map(Link::from_match).collect())
+        let locator = Locator::new(path, &content);
+
+        Ok(extractors
+            .find(path)
+            .extract(&content)
+            .into_iter()
+            .map(|(link, span)| (Link::new(&link), locator.locate(span)))
+            .collect())
     }
 
-    /// Check every link in `path` found by [`Links::find`].
+    /// Check every link in `links`, usually found by [`Links::find`].
     ///
-    /// Links in cache are skipped.  
-    /// Links ignored by cli arguments are skipped.  
-    /// Links determined alive are added to cache.
+    /// Links ignored by the configuration are skipped.  
+    /// Local links are resolved from `path`, the file containing them, and never cached.  
+    /// Links in cache reuse their cached status.  
+    /// Links already in `checked` are not requested again, others are requested with `client`.  
+    /// Failures are turned into dead links or warnings by the configured policy.  
+    /// Every checked link is added to cache.
     ///
     /// Returns the results of this file's check.
-    pub fn check(path: &PathBuf, cli: &Cli, cache: &mut Cache) -> Results {
+    #[cfg(feature = "check")]
+    pub fn check(
+        path: &Path,
+        links: &[(Link, Location)],
+        config: &CheckerConfig,
+        cache: &mut Cache,
+        client: &Client,
+        checked: &HashMap<Link, LinkStatus>,
+    ) -> Results {
         let mut results = Results::new();
 
-        for link in Links::find(path).unwrap_or_default() {
-            if cli.ignore.contains(&link) {
-                results.inserts(&link, LinkStatus::Ignored);
+        for (link, location) in links {
+            if config.ignores(link) {
+                results.inserts(link, location, LinkStatus::Ignored);
                 continue;
             }
 
-            if cache.contains(&link) {
-                results.inserts(&link, LinkStatus::Cached);
+            if link.is_local() {
+                let status = link.exists(path, config.root_dir.as_deref());
+                results.inserts(link, location, config.policy.apply(status));
                 continue;
             }
 
-            let status = link.alive();
-
-            if status == LinkStatus::Alive {
-                cache.inserts(&link);
+            if let Some(status) = cache.get(link) {
+                let status = status.clone().reaccepted(config.accept.codes(link));
+                results.inserts(link, location, config.policy.apply(status.cached()));
+                continue;
             }
 
-            results.inserts(&link, status);
+            let status = checked
+                .get(link)
+                .cloned()
+                .unwrap_or_else(|| link.alive(client, config.method, &config.accept));
+
+            cache.inserts(link, status.clone());
+
+            results.inserts(link, location, config.policy.apply(status));
         }
 
         results
@@ -0,0 +1,215 @@
+//! Module defining which status codes make a link alive.
+//!
+//! Accepted codes are configured globally and can be overridden per host.
+
+use std::collections::HashMap;
+use std::fmt::{Display, Formatter};
+use std::ops::RangeInclusive;
+
+use http::StatusCode;
+
+use crate::files::links::link::Link;
+
+/// Represents a set of accepted status codes, such as `200..=299,401,403`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Codes {
+    /// Ranges of accepted codes, a single code being a range of one
+    ranges: Vec<RangeInclusive<u16>>,
+}
+
+impl Default for Codes {
+    fn default() -> Self {
+        Self {
+            ranges: vec![200..=299],
+        }
+    }
+}
+
+impl Codes {
+    /// Parses a comma separated list of codes and ranges.
+    ///
+    /// Ranges are either inclusive (`200..=299`) or exclusive (`200..300`).
+    pub fn parse(s: &str) -> Result<Self, String> {
+        let ranges = s
+            .split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(Self::parse_range)
+            .collect::<Result<Vec<_>, _>>()?;
+
+        if ranges.is_empty() {
+            return Err(String::from("at least one status code is expected"));
+        }
+
+        Ok(Self { ranges })
+    }
+
+    /// Whether `status` is accepted
+    pub fn contains(&self, status: StatusCode) -> bool {
+        self.ranges
+            .iter()
+            .any(|range| range.contains(&status.as_u16()))
+    }
+
+    /// Parses a single code or range
+    fn parse_range(s: &str) -> Result<RangeInclusive<u16>, String> {
+        let code = |s: &str| {
+            s.trim()
+                .parse::<u16>()
+                .map_err(|_| format!("`{}` is not a valid status code", s))
+        };
+
+        let range = if let Some((start, end)) = s.split_once("..=") {
+            code(start)?..=code(end)?
+        } else if let Some((start, end)) = s.split_once("..") {
+            code(start)?..=code(end)?.saturating_sub(1)
+        } else {
+            code(s)?..=code(s)?
+        };
+
+        if range.is_empty() {
+            return Err(format!("`{}` is an empty range", s));
+        }
+
+        Ok(range)
+    }
+}
+
+impl Display for Codes {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        for (i, range) in self.ranges.iter().enumerate() {
+            if i > 0 {
+                write!(f, ",")?;
+            }
+
+            if range.start() == range.end() {
+                write!(f, "{}", range.start())?;
+            } else {
+                write!(f, "{}..={}", range.start(), range.end())?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Represents the accepted status codes, globally and per host
+#[derive(Debug, Clone, Default)]
+pub struct Accept {
+    /// Codes accepted for hosts without their own
+    global: Codes,
+    /// Codes accepted for specific hosts and their subdomains
+    hosts: HashMap<String, Codes>,
+}
+
+impl Accept {
+    /// Creates a new accepted status codes configuration.
+    ///
+    /// `hosts` override `global` for a host and its subdomains.
+    pub fn new(global: Codes, hosts: impl Iterator<Item = (String, Codes)>) -> Self {
+        Self {
+            global,
+            hosts: hosts.collect(),
+        }
+    }
+
+    /// Parses a host override such as `linkedin.com=200..=299,999`
+    pub fn parse_host(s: &str) -> Result<(String, Codes), String> {
+        let (host, codes) = s
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` should be formatted as <host>=<codes>", s))?;
+
+        Ok((host.trim().to_lowercase(), Codes::parse(codes)?))
+    }
+
+    /// Finds the codes accepted for `link`.
+    ///
+    /// The most specific host override applies, falling back to global codes.
+    pub fn codes(&self, link: &Link) -> &Codes {
+        let host = link.host().unwrap_or_default();
+
+        std::iter::successors(Some(host.as_str()), |host| {
+            host.split_once('.').map(|(_, parent)| parent)
+        })
+        .find_map(|host| self.hosts.get(host))
+        .unwrap_or(&self.global)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn status(code: u16) -> StatusCode {
+        StatusCode::from_u16(code).unwrap()
+    }
+
+    #[test]
+    fn default_success() {
+        let codes = Codes::default();
+
+        assert!(codes.contains(status(200)));
+        assert!(codes.contains(status(299)));
+        assert!(!codes.contains(status(301)));
+        assert!(!codes.contains(status(404)));
+    }
+
+    #[test]
+    fn parse() {
+        let codes = Codes::parse("200..=299, 401,403").unwrap();
+
+        assert!(codes.contains(status(204)));
+        assert!(codes.contains(status(401)));
+        assert!(codes.contains(status(403)));
+        assert!(!codes.contains(status(402)));
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(
+            Codes::parse("200..300, 401,403..=404").unwrap().to_string(),
+            "200..=299,401,403..=404"
+        );
+    }
+
+    #[test]
+    fn parse_exclusive_range() {
+        let codes = Codes::parse("200..300").unwrap();
+
+        assert!(codes.contains(status(299)));
+        assert!(!codes.contains(status(300)));
+    }
+
+    #[test]
+    fn parse_invalid() {
+        assert!(Codes::parse("").is_err());
+        assert!(Codes::parse("ok").is_err());
+        assert!(Codes::parse("300..=200").is_err());
+    }
+
+    #[test]
+    fn host_override() {
+        let accept = Accept::new(
+            Codes::default(),
+            vec![Accept::parse_host("linkedin.com=999").unwrap()].into_iter(),
+        );
+
+        let link = |url: &str| Link::new(&url.to_string());
+
+        assert!(accept
+            .codes(&link("https://www.linkedin.com/in/someone"))
+            .contains(status(999)));
+        assert!(accept
+            .codes(&link("https://linkedin.com"))
+            .contains(status(999)));
+        assert!(!accept
+            .codes(&link("https://example.com"))
+            .contains(status(999)));
+    }
+
+    #[test]
+    fn parse_host_invalid() {
+        assert!(Accept::parse_host("linkedin.com").is_err());
+        assert!(Accept::parse_host("linkedin.com=ok").is_err());
+    }
+}
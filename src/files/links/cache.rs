@@ -1,16 +1,38 @@
 //! Module defining cache related utilities.
 //!
 //! The cache aims to not check a link twice.
-//! For now, only valid links are stored and invalid links are re-checked every time.
+//! The full status of every checked link is stored, so repeated links reuse the first verdict.
+//!
+//! The cache can be saved to a file and loaded by a later run.
+//! Each line of the file holds the check time (seconds since Unix epoch), the status and the URL, separated by tabs.
+//! The status is either `alive`, or `dead`/`warn` followed by the reason and the number of attempts.
+//! Only alive links and status code failures are saved, transient failures (timeouts, DNS errors ...)
+//! are only reused within a run, so a later run checks them again.
+
+use std::collections::HashMap;
+use std::fs::{read_to_string, write};
+use std::io;
+use std::path::Path;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
 
-use std::collections::HashSet;
+use http::StatusCode;
 
-use crate::files::links::link::Link;
+use crate::files::links::link::{Failure, Link, LinkStatus};
+use crate::files::links::reason::Reason;
+
+/// A single cached link
+struct Entry {
+    /// Status the link was checked with
+    status: LinkStatus,
+    /// When the link was checked
+    checked: SystemTime,
+}
 
 /// Represents the links cache
 pub struct Cache {
+    /// Cached links by URL
     #[doc(hidden)]
-    data: HashSet<String>,
+    data: HashMap<String, Entry>,
 }
 
 impl Default for Cache {
@@ -23,18 +45,137 @@ impl Cache {
     /// Creates a new empty cache
     pub fn new() -> Self {
         Self {
-            data: HashSet::new(),
+            data: HashMap::new(),
+        }
+    }
+
+    /// Loads the cache saved at `path`.
+    ///
+    /// Entries checked more than `max_age` ago and malformed lines are left out.
+    pub fn load(path: &Path, max_age: Duration) -> io::Result<Self> {
+        let now = SystemTime::now();
+        let mut cache = Self::new();
+
+        for (url, entry) in read_to_string(path)?.lines().filter_map(Self::parse_line) {
+            let fresh = now.duration_since(entry.checked).unwrap_or_default() <= max_age;
+
+            if fresh && Self::persistent(&entry.status) {
+                cache.data.insert(url.to_string(), entry);
+            }
         }
+
+        Ok(cache)
+    }
+
+    /// Saves the cache to `path`, overwriting it.
+    ///
+    /// Transient failures are left out.
+    pub fn save(&self, path: &Path) -> io::Result<()> {
+        let mut content = String::new();
+
+        for (url, entry) in self
+            .data
+            .iter()
+            .filter(|(_, entry)| Self::persistent(&entry.status))
+        {
+            let checked = entry.checked.duration_since(UNIX_EPOCH).unwrap_or_default();
+
+            content.push_str(&format!(
+                "{}\t{}\t{}\n",
+                checked.as_secs(),
+                Self::format_status(&entry.status),
+                url
+            ));
+        }
+
+        write(path, content)
     }
 
     /// Check if the cache contains the given link
     pub fn contains(&self, link: &Link) -> bool {
-        self.data.contains(&link.to_string())
+        self.data.contains_key(link.as_str())
+    }
+
+    /// Returns the status the given link was checked with, if cached
+    pub fn get(&self, link: &Link) -> Option<&LinkStatus> {
+        self.data.get(link.as_str()).map(|entry| &entry.status)
     }
 
-    /// Inserts a new link in the cache
-    pub fn inserts(&mut self, link: &Link) {
-        self.data.insert(link.to_string());
+    /// Inserts a new link in the cache with its `status`
+    pub fn inserts(&mut self, link: &Link, status: LinkStatus) {
+        self.data.insert(
+            link.as_str().to_string(),
+            Entry {
+                status,
+                checked: SystemTime::now(),
+            },
+        );
+    }
+
+    /// Whether `status` is kept between runs, that is not a transient failure
+    fn persistent(status: &LinkStatus) -> bool {
+        match status {
+            LinkStatus::Dead(failure) | LinkStatus::Warn(failure) => {
+                matches!(failure.reason, Reason::Status(_))
+            }
+            _ => true,
+        }
+    }
+
+    /// Formats a status for the cache file
+    fn format_status(status: &LinkStatus) -> String {
+        let (verdict, failure) = match status {
+            LinkStatus::Dead(failure) => ("dead", failure),
+            LinkStatus::Warn(failure) => ("warn", failure),
+            _ => return String::from("alive"),
+        };
+
+        let reason = match failure.reason {
+            Reason::Status(status) => status.as_u16().to_string(),
+            ref reason => reason.name().to_string(),
+        };
+
+        format!("{} {} {}", verdict, reason, failure.attempts)
+    }
+
+    /// Parses a status of the cache file
+    fn parse_status(s: &str) -> Option<LinkStatus> {
+        let mut fields = s.split(' ');
+
+        let verdict = fields.next()?;
+
+        if verdict == "alive" {
+            return Some(LinkStatus::Alive(1));
+        }
+
+        let reason = fields.next()?;
+        let reason = match reason.parse::<u16>() {
+            Ok(code) => Reason::Status(StatusCode::from_u16(code).ok()?),
+            Err(_) => Reason::from_name(reason)?,
+        };
+        let failure = Failure::new(reason, fields.next()?.parse().ok()?);
+
+        match verdict {
+            "dead" => Some(LinkStatus::Dead(failure)),
+            "warn" => Some(LinkStatus::Warn(failure)),
+            _ => None,
+        }
+    }
+
+    /// Parses a line of a cache file into a URL and its entry
+    fn parse_line(line: &str) -> Option<(&str, Entry)> {
+        let mut fields = line.splitn(3, '\t');
+
+        let checked = fields.next()?.parse::<u64>().ok()?;
+        let status = Self::parse_status(fields.next()?)?;
+
+        Some((
+            fields.next()?,
+            Entry {
+                status,
+                checked: UNIX_EPOCH + Duration::from_secs(checked),
+            },
+        ))
     }
 }
 
@@ -57,7 +198,7 @@ mod tests {
     fn inserts() {
         let mut cache = Cache::new();
 
-        cache.inserts(&link());
+        cache.inserts(&link(), LinkStatus::Alive(1));
 
         assert_eq!(cache.data.len(), 1);
     }
@@ -74,7 +215,7 @@ mod tests {
     fn contains_after_inserted() {
         let mut cache = Cache::new();
 
-        cache.inserts(&link());
+        cache.inserts(&link(), LinkStatus::Alive(1));
 
         assert!(cache.contains(&link()));
     }
@@ -83,8 +224,101 @@ mod tests {
     fn not_contains_different_link() {
         let mut cache = Cache::new();
 
-        cache.inserts(&link());
+        cache.inserts(&link(), LinkStatus::Alive(1));
 
         assert!(!cache.contains(&Link::new(&"".to_string())));
     }
+
+    #[test]
+    fn save_and_load() {
+        let path = std::env::temp_dir().join("deadlinkrs-cache-save-and-load");
+        let mut cache = Cache::new();
+
+        cache.inserts(&link(), LinkStatus::Alive(1));
+        cache.save(&path).unwrap();
+
+        let loaded = Cache::load(&path, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(loaded.get(&link()), Some(&LinkStatus::Alive(1)));
+    }
+
+    #[test]
+    fn transient_failures_not_saved() {
+        let path = std::env::temp_dir().join("deadlinkrs-cache-transient");
+        let timeout = Link::new(&"https://example.org".to_string());
+        let mut cache = Cache::new();
+
+        cache.inserts(&link(), LinkStatus::Alive(1));
+        cache.inserts(&timeout, LinkStatus::Warn(Failure::new(Reason::Timeout, 1)));
+        cache.save(&path).unwrap();
+
+        assert!(cache.contains(&timeout));
+
+        let loaded = Cache::load(&path, Duration::from_secs(60)).unwrap();
+
+        assert!(loaded.contains(&link()));
+        assert!(!loaded.contains(&timeout));
+    }
+
+    #[test]
+    fn load_skips_transient_failures() {
+        let path = std::env::temp_dir().join("deadlinkrs-cache-load-transient");
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        write(
+            &path,
+            format!(
+                "{now}\twarn dns 1\thttps://example.org\n{now}\tdead 404 1\thttps://example.com\n",
+                now = now
+            ),
+        )
+        .unwrap();
+
+        let loaded = Cache::load(&path, Duration::from_secs(60)).unwrap();
+
+        assert!(!loaded.contains(&Link::new(&"https://example.org".to_string())));
+        assert!(loaded.contains(&link()));
+    }
+
+    #[test]
+    fn get_full_status() {
+        let mut cache = Cache::new();
+        let status = LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1));
+
+        cache.inserts(&link(), status.clone());
+
+        assert_eq!(cache.get(&link()), Some(&status));
+    }
+
+    #[test]
+    fn format_and_parse_status() {
+        let statuses = [
+            LinkStatus::Alive(1),
+            LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1)),
+            LinkStatus::Warn(Failure::new(Reason::Dns, 3)),
+        ];
+
+        for status in statuses {
+            assert_eq!(
+                Cache::parse_status(&Cache::format_status(&status)),
+                Some(status)
+            );
+        }
+    }
+
+    #[test]
+    fn load_skips_expired_and_malformed() {
+        let path = std::env::temp_dir().join("deadlinkrs-cache-expired");
+        write(
+            &path,
+            "0\talive\thttps://example.com\nmalformed line\nnow\talive\thttps://example.org\n",
+        )
+        .unwrap();
+
+        let loaded = Cache::load(&path, Duration::from_secs(60)).unwrap();
+
+        assert_eq!(loaded.data.len(), 0);
+    }
 }
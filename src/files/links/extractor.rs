@@ -0,0 +1,231 @@
+//! Module defining how links are extracted from a file, depending on its type.
+//!
+//! Every [`Extractor`] is registered for the files matching a glob.
+//! Files matching no glob fall back to the [`PlainExtractor`].
+
+use std::ops::Range;
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+
+use crate::files::links::{html, markdown, plain};
+
+/// Extracts links from the content of a file.
+///
+/// Extractors are shared by the checker, which can be sent to other threads.
+pub trait Extractor: Send + Sync {
+    /// Extracts every link of `content`, with its byte span, in order of appearance.
+    ///
+    /// Links may be relative.
+    fn extract(&self, content: &str) -> Vec<(String, Range<usize>)>;
+}
+
+/// Extractor searching plain text for absolute URLs.
+///
+/// Only `http` and `https` URLs are found, trailing punctuation and unbalanced parentheses are trimmed.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PlainExtractor;
+
+impl Extractor for PlainExtractor {
+    fn extract(&self, content: &str) -> Vec<(String, Range<usize>)> {
+        plain::extract(content)
+    }
+}
+
+/// Extractor reading the attributes of HTML documents.
+///
+/// Links of `href`, `src`, `srcset` and `action` attributes are decoded from HTML entities,
+/// comments as well as `script` and `style` contents are skipped.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct HtmlExtractor;
+
+impl Extractor for HtmlExtractor {
+    fn extract(&self, content: &str) -> Vec<(String, Range<usize>)> {
+        html::extract(content)
+    }
+}
+
+/// Extractor parsing Markdown documents as CommonMark.
+///
+/// Inline links, images, autolinks, reference definitions, URLs in plain text and links of inline HTML are extracted.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct MarkdownExtractor {
+    /// URLs inside code spans and code blocks are extracted, they are skipped by default
+    pub code: bool,
+}
+
+impl Extractor for MarkdownExtractor {
+    fn extract(&self, content: &str) -> Vec<(String, Range<usize>)> {
+        markdown::extract(content, self.code)
+    }
+}
+
+/// Represents the extractors registered per glob
+pub struct Extractors {
+    /// Extractors with the glob of the files they apply to, the last registered first
+    rules: Vec<(GlobMatcher, Box<dyn Extractor>)>,
+    /// Extractor of files matching no glob
+    fallback: Box<dyn Extractor>,
+}
+
+impl Default for Extractors {
+    fn default() -> Self {
+        Self::with_markdown(MarkdownExtractor::default())
+    }
+}
+
+impl Extractors {
+    /// Names of the built-in extractors, as accepted by cli arguments
+    pub const NAMES: [&'static str; 3] = ["plain", "html", "markdown"];
+
+    /// Creates a new registry without any extractor, every file falling back to [`PlainExtractor`]
+    pub fn new() -> Self {
+        Self {
+            rules: Vec::new(),
+            fallback: Box::new(PlainExtractor),
+        }
+    }
+
+    /// Creates the default registry, with HTML files parsed by [`HtmlExtractor`]
+    /// and Markdown files parsed by `markdown`.
+    pub fn with_markdown(markdown: MarkdownExtractor) -> Self {
+        let mut extractors = Self::new();
+
+        for glob in ["*.html", "*.htm"] {
+            extractors
+                .register(glob, Box::new(HtmlExtractor))
+                .expect("default extractor glob should be valid");
+        }
+        for glob in ["*.md", "*.markdown"] {
+            extractors
+                .register(glob, Box::new(markdown))
+                .expect("default extractor glob should be valid");
+        }
+
+        extractors
+    }
+
+    /// Creates the built-in extractor named `name`, one of [`Extractors::NAMES`]
+    pub fn from_name(name: &str) -> Option<Box<dyn Extractor>> {
+        match name {
+            "plain" => Some(Box::new(PlainExtractor)),
+            "html" => Some(Box::new(HtmlExtractor)),
+            "markdown" => Some(Box::new(MarkdownExtractor::default())),
+            _ => None,
+        }
+    }
+
+    /// Parses a rule such as `*.txt=markdown` into a glob and an extractor name
+    pub fn parse_rule(s: &str) -> Result<(String, String), String> {
+        let (glob, name) = s
+            .split_once('=')
+            .ok_or_else(|| format!("`{}` should be formatted as <glob>=<extractor>", s))?;
+        let (glob, name) = (glob.trim(), name.trim());
+
+        Self::matcher(glob).map_err(|e| e.to_string())?;
+
+        if Self::from_name(name).is_none() {
+            return Err(format!(
+                "`{}` is not an extractor, expected one of {}",
+                name,
+                Self::NAMES.join(", ")
+            ));
+        }
+
+        Ok((glob.to_string(), name.to_string()))
+    }
+
+    /// Registers `extractor` for files matching `glob`, ignoring case.
+    ///
+    /// It takes precedence over every extractor registered before.
+    pub fn register(
+        &mut self,
+        glob: &str,
+        extractor: Box<dyn Extractor>,
+    ) -> Result<(), globset::Error> {
+        self.rules.insert(0, (Self::matcher(glob)?, extractor));
+
+        Ok(())
+    }
+
+    /// Finds the extractor of the file at `path`
+    pub fn find(&self, path: &Path) -> &dyn Extractor {
+        self.rules
+            .iter()
+            .find(|(glob, _)| glob.is_match(path))
+            .map_or(self.fallback.as_ref(), |(_, extractor)| extractor.as_ref())
+    }
+
+    /// Compiles `glob` to match paths, ignoring case
+    fn matcher(glob: &str) -> Result<GlobMatcher, globset::Error> {
+        Ok(GlobBuilder::new(glob)
+            .case_insensitive(true)
+            .build()?
+            .compile_matcher())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Extractor finding a single fake link, to tell which extractor was used
+    struct Named(&'static str);
+
+    impl Extractor for Named {
+        fn extract(&self, _content: &str) -> Vec<(String, Range<usize>)> {
+            vec![(self.0.to_string(), 0..0)]
+        }
+    }
+
+    fn extracted(extractors: &Extractors, path: &str, content: &str) -> Vec<String> {
+        extractors
+            .find(Path::new(path))
+            .extract(content)
+            .into_iter()
+            .map(|(link, _)| link)
+            .collect()
+    }
+
+    #[test]
+    fn defaults() {
+        let extractors = Extractors::default();
+        let content = "`https://example.com/code` <a href=\"https://example.com/html\">";
+
+        assert_eq!(
+            extracted(&extractors, "docs/index.HTML", content),
+            ["https://example.com/html"]
+        );
+        assert_eq!(
+            extracted(&extractors, "docs/README.md", content),
+            ["https://example.com/html"]
+        );
+        assert_eq!(
+            extracted(&extractors, "src/main.rs", content),
+            ["https://example.com/code", "https://example.com/html"]
+        );
+    }
+
+    #[test]
+    fn last_registered_first() {
+        let mut extractors = Extractors::default();
+
+        extractors
+            .register("docs/*.md", Box::new(Named("custom")))
+            .unwrap();
+
+        assert_eq!(extracted(&extractors, "docs/README.md", ""), ["custom"]);
+        assert!(extracted(&extractors, "README.md", "").is_empty());
+    }
+
+    #[test]
+    fn parse_rule() {
+        assert_eq!(
+            Extractors::parse_rule("*.txt = markdown"),
+            Ok((String::from("*.txt"), String::from("markdown")))
+        );
+        assert!(Extractors::parse_rule("*.txt").is_err());
+        assert!(Extractors::parse_rule("*.txt=unknown").is_err());
+        assert!(Extractors::parse_rule("a/{b=html").is_err());
+    }
+}
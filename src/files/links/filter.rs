@@ -0,0 +1,111 @@
+//! Module defining which links are checked.
+//!
+//! Links are filtered by regular expressions, compiled once into sets.
+
+use regex::{Regex, RegexSet};
+
+use crate::files::links::link::Link;
+
+/// Represents the links ignored and included by cli arguments
+pub struct Filter {
+    /// Links matching any of these are ignored
+    ignore: RegexSet,
+    /// When set, links not matching any of these are ignored
+    include: Option<RegexSet>,
+}
+
+impl Default for Filter {
+    fn default() -> Self {
+        Self {
+            ignore: RegexSet::empty(),
+            include: None,
+        }
+    }
+}
+
+impl Filter {
+    /// Creates a new filter ignoring links matching any regex of `ignore`.
+    ///
+    /// If `include` is not empty, only links matching one of its regex are kept.
+    pub fn new<'a>(
+        ignore: impl Iterator<Item = &'a String>,
+        include: impl Iterator<Item = &'a String>,
+    ) -> Result<Self, regex::Error> {
+        let include = RegexSet::new(include)?;
+
+        Ok(Self {
+            ignore: RegexSet::new(ignore)?,
+            include: if include.is_empty() {
+                None
+            } else {
+                Some(include)
+            },
+        })
+    }
+
+    /// Validates a single regex, as given to cli arguments
+    pub fn validate(s: &str) -> Result<String, String> {
+        Regex::new(s)
+            .map(|_| s.to_string())
+            .map_err(|e| e.to_string())
+    }
+
+    /// Whether `link` should be ignored
+    pub fn ignores(&self, link: &Link) -> bool {
+        self.ignore.is_match(link.as_str())
+            || self
+                .include
+                .as_ref()
+                .map_or(false, |include| !include.is_match(link.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn patterns(patterns: &[&str]) -> Vec<String> {
+        patterns.iter().map(|p| p.to_string()).collect()
+    }
+
+    fn link(url: &str) -> Link {
+        Link::new(&url.to_string())
+    }
+
+    #[test]
+    fn default_ignores_nothing() {
+        assert!(!Filter::default().ignores(&link("https://example.com")));
+    }
+
+    #[test]
+    fn ignore() {
+        let filter = Filter::new(
+            patterns(&["^tel:", "^mailto:", "://localhost[:/]"]).iter(),
+            [].iter(),
+        )
+        .unwrap();
+
+        assert!(filter.ignores(&link("tel:+33123456789")));
+        assert!(filter.ignores(&link("http://localhost:8080/index.html")));
+        assert!(!filter.ignores(&link("https://example.com")));
+    }
+
+    #[test]
+    fn include() {
+        let filter = Filter::new(
+            patterns(&["/private/"]).iter(),
+            patterns(&["^https://example\\.com"]).iter(),
+        )
+        .unwrap();
+
+        assert!(!filter.ignores(&link("https://example.com/public/")));
+        assert!(filter.ignores(&link("https://example.com/private/")));
+        assert!(filter.ignores(&link("https://example.org")));
+    }
+
+    #[test]
+    fn invalid_regex() {
+        assert!(Filter::new(patterns(&["("]).iter(), [].iter()).is_err());
+        assert!(Filter::validate("(").is_err());
+    }
+}
@@ -0,0 +1,446 @@
+//! Module extracting links from HTML documents.
+//!
+//! Tags are tokenized just enough to read their attributes,
+//! so the byte span of every link is kept.
+//! Comments are skipped, as well as the content of `script` and `style` elements.
+
+use std::ops::Range;
+
+/// Tags and attributes holding links, `srcset` attributes holding several of them
+const ATTRIBUTES: [(&str, &str); 9] = [
+    ("a", "href"),
+    ("link", "href"),
+    ("img", "src"),
+    ("img", "srcset"),
+    ("script", "src"),
+    ("source", "src"),
+    ("source", "srcset"),
+    ("iframe", "src"),
+    ("form", "action"),
+];
+
+/// Schemes of URLs that are not links to a resource
+const IGNORED_SCHEMES: [&str; 2] = ["javascript:", "data:"];
+
+/// A single tag, with the byte span of every attribute value
+struct Tag {
+    /// Lowercase name of the tag
+    name: String,
+    /// Lowercase names of the attributes, with the span of their raw value
+    attributes: Vec<(String, Range<usize>)>,
+    /// Byte offset right after the tag
+    end: usize,
+}
+
+impl Tag {
+    /// Finds the span of the raw value of attribute `name`
+    fn attribute(&self, name: &str) -> Option<Range<usize>> {
+        self.attributes
+            .iter()
+            .find(|(attribute, _)| attribute == name)
+            .map(|(_, span)| span.clone())
+    }
+}
+
+/// Extracts every link of the HTML `content`, with its byte span.
+///
+/// Links are decoded from HTML entities, and may be relative.
+pub fn extract(content: &str) -> Vec<(String, Range<usize>)> {
+    let lower = content.to_ascii_lowercase();
+    let mut links = Vec::new();
+    let mut i = 0;
+
+    while let Some(start) = content[i..].find('<').map(|offset| i + offset) {
+        let rest = &content[start..];
+
+        i = if rest.starts_with("<!--") {
+            skip_past(&lower, start + 4, "-->")
+        } else if rest.starts_with("<!") || rest.starts_with("<?") || rest.starts_with("</") {
+            skip_past(&lower, start, ">")
+        } else if let Some(tag) = parse_tag(content, start) {
+            links.extend(tag_links(content, &tag));
+
+            if tag.name == "script" || tag.name == "style" {
+                skip_past(&lower, tag.end, &format!("</{}", tag.name))
+            } else {
+                tag.end
+            }
+        } else {
+            start + 1
+        };
+    }
+
+    links
+        .into_iter()
+        .filter(|(link, _)| {
+            !link.is_empty()
+                && !IGNORED_SCHEMES
+                    .iter()
+                    .any(|scheme| link.to_ascii_lowercase().starts_with(scheme))
+        })
+        .collect()
+}
+
+/// Byte offset right after the first `pattern` found from `from`, or the end of `content`
+fn skip_past(content: &str, from: usize, pattern: &str) -> usize {
+    content[from..]
+        .find(pattern)
+        .map_or(content.len(), |offset| from + offset + pattern.len())
+}
+
+/// Parses the opening tag starting at byte `start`, the `<` character.
+///
+/// As in HTML, a tag name starts with an ASCII letter, so text such as `<3` is not a tag.
+fn parse_tag(content: &str, start: usize) -> Option<Tag> {
+    let bytes = content.as_bytes();
+    let mut i = start + 1;
+
+    if !bytes.get(i).map_or(false, u8::is_ascii_alphabetic) {
+        return None;
+    }
+
+    while i < bytes.len() && bytes[i].is_ascii_alphanumeric() {
+        i += 1;
+    }
+
+    let name = content[start + 1..i].to_ascii_lowercase();
+    let mut attributes = Vec::new();
+
+    loop {
+        while i < bytes.len() && (bytes[i].is_ascii_whitespace() || bytes[i] == b'/') {
+            i += 1;
+        }
+
+        if i >= bytes.len() {
+            break;
+        }
+        if bytes[i] == b'>' {
+            i += 1;
+            break;
+        }
+
+        let name_start = i;
+        while i < bytes.len()
+            && !bytes[i].is_ascii_whitespace()
+            && !matches!(bytes[i], b'=' | b'>' | b'/')
+        {
+            i += 1;
+        }
+        let attribute = content[name_start..i].to_ascii_lowercase();
+
+        let mut j = i;
+        while j < bytes.len() && bytes[j].is_ascii_whitespace() {
+            j += 1;
+        }
+
+        if j >= bytes.len() || bytes[j] != b'=' {
+            attributes.push((attribute, i..i));
+            continue;
+        }
+
+        i = j + 1;
+        while i < bytes.len() && bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        let value = match bytes.get(i) {
+            Some(quote @ (b'"' | b'\'')) => {
+                let end = content[i + 1..]
+                    .find(*quote as char)
+                    .map_or(content.len(), |offset| i + 1 + offset);
+                let value = i + 1..end;
+                i = (end + 1).min(content.len());
+                value
+            }
+            _ => {
+                let value_start = i;
+                while i < bytes.len() && !bytes[i].is_ascii_whitespace() && bytes[i] != b'>' {
+                    i += 1;
+                }
+                value_start..i
+            }
+        };
+
+        attributes.push((attribute, value));
+    }
+
+    Some(Tag {
+        name,
+        attributes,
+        end: i,
+    })
+}
+
+/// Extracts the links held by the attributes of `tag`
+fn tag_links(content: &str, tag: &Tag) -> Vec<(String, Range<usize>)> {
+    let mut links = Vec::new();
+
+    for (name, attribute) in ATTRIBUTES {
+        if tag.name != name {
+            continue;
+        }
+
+        if let Some(span) = tag.attribute(attribute) {
+            if attribute == "srcset" {
+                links.extend(srcset(content, span));
+            } else {
+                links.push(link(content, span));
+            }
+        }
+    }
+
+    let refresh = tag.name == "meta"
+        && tag.attribute("http-equiv").map_or(false, |span| {
+            content[span].trim().eq_ignore_ascii_case("refresh")
+        });
+
+    if refresh {
+        if let Some(span) = tag.attribute("content") {
+            links.extend(refresh_url(content, span).map(|span| link(content, span)));
+        }
+    }
+
+    links
+}
+
+/// Decodes the link in `span`, leading and trailing whitespaces excluded
+fn link(content: &str, span: Range<usize>) -> (String, Range<usize>) {
+    let raw = &content[span.clone()];
+    let start = span.start + (raw.len() - raw.trim_start().len());
+    let end = span.end - (raw.len() - raw.trim_end().len());
+    let span = start..end.max(start);
+
+    (decode(&content[span.clone()]), span)
+}
+
+/// Splits a `srcset` attribute in `span` into the spans of its URLs, descriptors excluded
+fn srcset(content: &str, span: Range<usize>) -> Vec<(String, Range<usize>)> {
+    let bytes = content.as_bytes();
+    let mut links = Vec::new();
+    let mut i = span.start;
+
+    while i < span.end {
+        while i < span.end && (bytes[i].is_ascii_whitespace() || bytes[i] == b',') {
+            i += 1;
+        }
+
+        let start = i;
+        while i < span.end && !bytes[i].is_ascii_whitespace() {
+            i += 1;
+        }
+
+        let mut end = i;
+        let trailing_comma = end > start && bytes[end - 1] == b',';
+        while end > start && bytes[end - 1] == b',' {
+            end -= 1;
+        }
+
+        if end > start {
+            links.push(link(content, start..end));
+        }
+
+        if !trailing_comma {
+            while i < span.end && bytes[i] != b',' {
+                i += 1;
+            }
+        }
+    }
+
+    links
+}
+
+/// Finds the span of the URL in the `content` attribute of a `meta refresh` tag, such as `5; url=https://example.com`
+fn refresh_url(content: &str, span: Range<usize>) -> Option<Range<usize>> {
+    let value = &content[span.clone()];
+    let delay = value.find([';', ','])?;
+    let rest = value[delay + 1..].trim_start();
+    let mut offset = value.len() - rest.len();
+
+    if rest
+        .get(..3)
+        .map_or(false, |prefix| prefix.eq_ignore_ascii_case("url"))
+    {
+        let after = rest[3..].trim_start();
+
+        if let Some(after) = after.strip_prefix('=') {
+            let after = after.trim_start();
+            offset = value.len() - after.len();
+        }
+    }
+
+    let url = &value[offset..];
+    let url = match url.chars().next() {
+        Some(quote @ ('"' | '\'')) => {
+            offset += 1;
+            let url = &url[1..];
+            &url[..url.find(quote).unwrap_or(url.len())]
+        }
+        _ => url,
+    };
+
+    if url.trim().is_empty() {
+        None
+    } else {
+        Some(span.start + offset..span.start + offset + url.len())
+    }
+}
+
+/// Decodes the HTML entities `&amp;`, `&quot;`, `&apos;`, `&lt;`, `&gt;` and numeric references in `s`.
+///
+/// Unknown entities are left untouched.
+fn decode(s: &str) -> String {
+    let mut decoded = String::with_capacity(s.len());
+    let mut rest = s;
+
+    while let Some(amp) = rest.find('&') {
+        decoded.push_str(&rest[..amp]);
+        rest = &rest[amp..];
+
+        let entity = rest
+            .find(';')
+            .filter(|end| *end <= 10)
+            .and_then(|end| Some((entity(&rest[1..end])?, end)));
+
+        match entity {
+            Some((c, end)) => {
+                decoded.push(c);
+                rest = &rest[end + 1..];
+            }
+            None => {
+                decoded.push('&');
+                rest = &rest[1..];
+            }
+        }
+    }
+
+    decoded.push_str(rest);
+    decoded
+}
+
+/// Decodes a single entity `name`, written between `&` and `;`
+fn entity(name: &str) -> Option<char> {
+    match name {
+        "amp" => Some('&'),
+        "quot" => Some('"'),
+        "apos" => Some('\''),
+        "lt" => Some('<'),
+        "gt" => Some('>'),
+        _ => {
+            let code = name.strip_prefix('#')?;
+            let code = match code.strip_prefix(|c| c == 'x' || c == 'X') {
+                Some(hex) => u32::from_str_radix(hex, 16).ok()?,
+                None => code.parse().ok()?,
+            };
+
+            char::from_u32(code)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(content: &str) -> Vec<String> {
+        extract(content).into_iter().map(|(link, _)| link).collect()
+    }
+
+    #[test]
+    fn attributes() {
+        let content = r#"
+            <a href="https://example.com/page">Page</a>
+            <A HREF='/relative'>Relative</A>
+            <link rel="stylesheet" href=style.css>
+            <img alt="logo" src="logo.png">
+            <script src="https://cdn.example.technology/app.js"></script>
+            <video><source src="movie.mp4" type="video/mp4"></video>
+            <iframe src="https://www.youtube.com/embed/id"></iframe>
+            <form action="/search" method="get"></form>
+            I <3 you <a href="https://example.com/lost">Lost</a>
+        "#;
+
+        assert_eq!(
+            links(content),
+            [
+                "https://example.com/page",
+                "/relative",
+                "style.css",
+                "logo.png",
+                "https://cdn.example.technology/app.js",
+                "movie.mp4",
+                "https://www.youtube.com/embed/id",
+                "/search",
+                "https://example.com/lost"
+            ]
+        );
+    }
+
+    #[test]
+    fn srcset() {
+        let content = r#"<img srcset="small.jpg 480w, large.jpg 1080w,hd.jpg" src="small.jpg">"#;
+
+        assert_eq!(
+            links(content),
+            ["small.jpg", "small.jpg", "large.jpg", "hd.jpg"]
+        );
+    }
+
+    #[test]
+    fn meta_refresh() {
+        assert_eq!(
+            links(r#"<meta http-equiv="refresh" content="5; URL='https://example.com/new'">"#),
+            ["https://example.com/new"]
+        );
+        assert_eq!(
+            links(r#"<meta http-equiv="Refresh" content="0;url=/home">"#),
+            ["/home"]
+        );
+        assert_eq!(
+            links(r#"<meta http-equiv="refresh" content="0; éé">"#),
+            ["éé"]
+        );
+        assert_eq!(
+            links(r#"<meta http-equiv="refresh" content="0;ürl=/ünïcode">"#),
+            ["ürl=/ünïcode"]
+        );
+        assert!(links(r#"<meta http-equiv="refresh" content="30">"#).is_empty());
+        assert!(links(r#"<meta name="description" content="0; url=/home">"#).is_empty());
+    }
+
+    #[test]
+    fn spans() {
+        let content = r#"<a href=" https://example.com/?a=1&amp;b=2 ">"#;
+
+        let (link, span) = extract(content).pop().unwrap();
+
+        assert_eq!(link, "https://example.com/?a=1&b=2");
+        assert_eq!(&content[span], "https://example.com/?a=1&amp;b=2");
+    }
+
+    #[test]
+    fn skips_comments_and_scripts() {
+        let content = r#"
+            <!-- <a href="https://commented.example.com"> -->
+            <script>document.write('<a href="https://script.example.com">')</script>
+            <style>a::after { content: '<img src="x.png">' }</style>
+            <a href="https://example.com">
+        "#;
+
+        assert_eq!(links(content), ["https://example.com"]);
+    }
+
+    #[test]
+    fn ignored_schemes() {
+        let content = r##"<a href="javascript:void(0)"></a><a href=""></a><img src="data:image/png;base64,AAA"><a href="#top"></a>"##;
+
+        assert_eq!(links(content), ["#top"]);
+    }
+
+    #[test]
+    fn decode_entities() {
+        assert_eq!(
+            decode("a&amp;b&#38;c&#x26;d&unknown;e&"),
+            "a&b&c&d&unknown;e&"
+        );
+    }
+}
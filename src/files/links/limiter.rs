@@ -0,0 +1,185 @@
+//! Module defining per host rate limiting.
+//!
+//! The limiter keeps checks polite towards a single host,
+//! by spacing requests and bounding the number of requests in flight.
+
+use std::collections::HashMap;
+use std::sync::{Condvar, Mutex};
+use std::thread::sleep;
+use std::time::{Duration, Instant};
+
+use crate::files::links::link::Link;
+
+/// Budget of a single host
+struct Host {
+    /// Number of requests currently sent to this host
+    in_flight: usize,
+    /// Earliest instant the next request to this host can be sent
+    next: Instant,
+}
+
+/// Represents the per host request budget
+pub struct Limiter {
+    /// Minimum delay between two requests to the same host
+    interval: Duration,
+    /// Maximum number of requests in flight to the same host
+    max_in_flight: usize,
+    /// Budget of every host seen so far
+    hosts: Mutex<HashMap<String, Host>>,
+    /// Notified every time a request is done
+    done: Condvar,
+}
+
+/// Allows a single request to a host, the budget is released on drop
+pub struct Permit<'a> {
+    /// Limiter this permit was acquired from
+    limiter: &'a Limiter,
+    /// Host of the request
+    host: String,
+}
+
+impl Default for Limiter {
+    fn default() -> Self {
+        Self::new(None, None)
+    }
+}
+
+impl Limiter {
+    /// Creates a new limiter.
+    ///
+    /// `rps` is the maximum number of requests per second to a single host,
+    /// `max_in_flight` is the maximum number of requests in flight to a single host.
+    /// Both are unlimited when `None`, as well as rates rejected by the [`CheckerBuilder`](crate::checker::CheckerBuilder).
+    pub fn new(rps: Option<f64>, max_in_flight: Option<usize>) -> Self {
+        Self {
+            interval: rps
+                .filter(|rps| *rps > 0.0)
+                .and_then(|rps| Duration::try_from_secs_f64(1.0 / rps).ok())
+                .unwrap_or_default(),
+            max_in_flight: max_in_flight.unwrap_or(usize::MAX).max(1),
+            hosts: Mutex::new(HashMap::new()),
+            done: Condvar::new(),
+        }
+    }
+
+    /// Waits until `link`'s host budget allows a new request.
+    ///
+    /// The request is accounted for until the returned [`Permit`] is dropped.
+    pub fn acquire(&self, link: &Link) -> Permit<'_> {
+        let host = link.host().unwrap_or_default();
+        let mut hosts = self
+            .hosts
+            .lock()
+            .expect("Limiter lock should not be poisoned");
+
+        loop {
+            let now = Instant::now();
+            let budget = hosts.entry(host.clone()).or_insert(Host {
+                in_flight: 0,
+                next: now,
+            });
+
+            if budget.in_flight < self.max_in_flight {
+                let slot = budget.next.max(now);
+
+                budget.in_flight += 1;
+                budget.next = slot + self.interval;
+                drop(hosts);
+
+                sleep(slot - now);
+
+                return Permit {
+                    limiter: self,
+                    host,
+                };
+            }
+
+            hosts = self
+                .done
+                .wait(hosts)
+                .expect("Limiter lock should not be poisoned");
+        }
+    }
+
+    /// Releases a request to `host`
+    fn release(&self, host: &str) {
+        let mut hosts = self
+            .hosts
+            .lock()
+            .expect("Limiter lock should not be poisoned");
+
+        if let Some(budget) = hosts.get_mut(host) {
+            budget.in_flight -= 1;
+        }
+
+        self.done.notify_all();
+    }
+}
+
+impl Drop for Permit<'_> {
+    fn drop(&mut self) {
+        self.limiter.release(&self.host);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link() -> Link {
+        Link::new(&"https://example.com/a".to_string())
+    }
+
+    fn in_flight(limiter: &Limiter, host: &str) -> usize {
+        limiter.hosts.lock().unwrap().get(host).unwrap().in_flight
+    }
+
+    #[test]
+    fn unlimited_by_default() {
+        let limiter = Limiter::default();
+
+        assert_eq!(limiter.interval, Duration::ZERO);
+        assert_eq!(limiter.max_in_flight, usize::MAX);
+    }
+
+    #[test]
+    fn invalid_rates_unlimited() {
+        for rps in [0.0, -1.0, f64::NAN, 1e-20] {
+            assert_eq!(Limiter::new(Some(rps), None).interval, Duration::ZERO);
+        }
+    }
+
+    #[test]
+    fn permit_released_on_drop() {
+        let limiter = Limiter::new(None, Some(2));
+
+        let permit = limiter.acquire(&link());
+        assert_eq!(in_flight(&limiter, "example.com"), 1);
+
+        drop(permit);
+        assert_eq!(in_flight(&limiter, "example.com"), 0);
+    }
+
+    #[test]
+    fn hosts_are_independent() {
+        let limiter = Limiter::new(None, Some(1));
+
+        let _first = limiter.acquire(&link());
+        let _second = limiter.acquire(&Link::new(&"https://example.org".to_string()));
+
+        assert_eq!(in_flight(&limiter, "example.com"), 1);
+        assert_eq!(in_flight(&limiter, "example.org"), 1);
+    }
+
+    #[test]
+    fn requests_are_spaced() {
+        let limiter = Limiter::new(Some(20.0), None);
+        let start = Instant::now();
+
+        for _ in 0..3 {
+            limiter.acquire(&link());
+        }
+
+        assert!(start.elapsed() >= Duration::from_millis(100));
+    }
+}
@@ -1,35 +1,145 @@
 //! Groups data structures related to a single link.
 //!
-//! This module contains a link wrapper and a link status.
+//! This module contains a link wrapper, a link status and failure details.
 
+use std::env;
 use std::fmt::{Display, Formatter};
+use std::path::{Path, PathBuf};
 
+#[cfg(feature = "cli")]
 use ansi_term::Color::{Blue, Green, Red, Yellow};
+#[cfg(feature = "cli")]
 use ansi_term::Style;
 use regex::Match;
-use reqwest::blocking::Client;
+#[cfg(feature = "check")]
+use reqwest::blocking::{Client, Response};
+use url::{ParseError, Url};
+
+#[cfg(feature = "check")]
+use crate::files::links::accept::{Accept, Codes};
+#[cfg(feature = "cli")]
+use crate::files::links::location::Location;
+#[cfg(feature = "check")]
+use crate::files::links::method::Method;
+use crate::files::links::reason::Reason;
 
 /// Represents the status of a link in the process
 #[derive(Debug, Clone, PartialEq)]
 pub enum LinkStatus {
-    /// The link is alive (returned 2xx)
-    Alive,
-    /// The link is dead (returned other than 2xx).
-    /// The value is the reason.
+    /// The link is alive (returned an accepted status code, 2xx by default).
+    /// The value is the number of requests sent before it answered.
+    Alive(u32),
+    /// The link is dead (returned a status code not accepted).
+    /// The value details the failure.
     ///
     /// This has a chance of being false because some websites block bots.
-    Dead(String),
+    Dead(Failure),
     /// There was a problem getting a response
-    /// The value is the reason.
-    Warn(String),
+    /// The value details the failure.
+    Warn(Failure),
     /// Link was already tested and is in cache
     Cached,
     /// Link has been ignored
     Ignored,
 }
 
+impl LinkStatus {
+    /// Determines the status of a link from the `response` to its last request.
+    ///
+    /// `attempts` is the number of requests sent to get this response.
+    /// Status codes not in `accepted` make the link dead, request errors generate a warning.
+    #[cfg(feature = "check")]
+    pub fn from_response(
+        response: &reqwest::Result<Response>,
+        attempts: u32,
+        accepted: &Codes,
+    ) -> Self {
+        match response {
+            Ok(r) if accepted.contains(r.status()) => LinkStatus::Alive(attempts),
+            Ok(r) => LinkStatus::Dead(Failure::new(Reason::Status(r.status()), attempts)),
+            Err(e) => LinkStatus::Warn(Failure::new(Reason::from_error(e), attempts)),
+        }
+    }
+
+    /// Makes a status code failure alive if its code is now in `accepted`.
+    ///
+    /// Accepted codes may have changed since the status was cached.
+    #[cfg(feature = "check")]
+    pub fn reaccepted(self, accepted: &Codes) -> Self {
+        match self {
+            LinkStatus::Dead(Failure {
+                reason: Reason::Status(code),
+                attempts,
+                ..
+            })
+            | LinkStatus::Warn(Failure {
+                reason: Reason::Status(code),
+                attempts,
+                ..
+            }) if accepted.contains(code) => LinkStatus::Alive(attempts),
+            status => status,
+        }
+    }
+
+    /// Marks this status as coming from the cache.
+    ///
+    /// Alive links become [cached](LinkStatus::Cached), failures are flagged as cached.
+    pub fn cached(self) -> Self {
+        match self {
+            LinkStatus::Alive(_) | LinkStatus::Cached => LinkStatus::Cached,
+            LinkStatus::Dead(failure) => LinkStatus::Dead(Failure {
+                cached: true,
+                ..failure
+            }),
+            LinkStatus::Warn(failure) => LinkStatus::Warn(Failure {
+                cached: true,
+                ..failure
+            }),
+            LinkStatus::Ignored => LinkStatus::Ignored,
+        }
+    }
+}
+
+/// Details why a link is not alive
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Failure {
+    /// Reason of the failure
+    pub reason: Reason,
+    /// Number of requests sent before giving up
+    pub attempts: u32,
+    /// The failure comes from the cache, the link was not requested again
+    pub cached: bool,
+}
+
+impl Failure {
+    /// Creates a new failure for `reason`, after `attempts` requests.
+    pub fn new(reason: Reason, attempts: u32) -> Self {
+        Self {
+            reason,
+            attempts,
+            cached: false,
+        }
+    }
+}
+
+impl Display for Failure {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.reason)?;
+
+        if self.attempts > 1 {
+            write!(f, " (after {} attempts)", self.attempts)?;
+        }
+
+        if self.cached {
+            write!(f, " (cached)")?;
+        }
+
+        Ok(())
+    }
+}
+
 /// Represents a single link that can be checked or formatted
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
 pub struct Link {
     #[doc(hidden)]
     link: String,
@@ -54,62 +164,149 @@ impl Link {
         }
     }
 
-    /// Check if link is alive
+    /// Returns the link as a plain string, without formatting
+    pub fn as_str(&self) -> &str {
+        &self.link
+    }
+
+    /// Whether the link is an absolute HTTP(S) URL, that can be requested.
     ///
-    /// Returns the status of the link.
-    /// The link can be [alive](LinkStatus::Alive), [dead](LinkStatus::Dead) or generate a [warning](LinkStatus::Warn).
-    pub fn alive(&self) -> LinkStatus {
-        // TODO: try to make a common client
-        let response = match Client::new().get(self.link.as_str()).send() {
-            Ok(r) => r,
-            Err(_) => {
-                return LinkStatus::Warn("Too many redirections".to_string());
-            }
+    /// Malformed URLs with an HTTP(S) scheme are remote too, so requesting them reports them as invalid.
+    pub fn is_remote(&self) -> bool {
+        let scheme = self.link.split_once(':').map_or("", |(scheme, _)| scheme);
+
+        scheme.eq_ignore_ascii_case("http") || scheme.eq_ignore_ascii_case("https")
+    }
+
+    /// Whether the link is a relative reference to a local file, such as `./guide.md` or `/docs/setup.html`.
+    ///
+    /// Network-path references such as `//example.com` are not local.
+    pub fn is_local(&self) -> bool {
+        matches!(
+            Url::parse(&self.link),
+            Err(ParseError::RelativeUrlWithoutBase)
+        ) && !self.link.starts_with("//")
+    }
+
+    /// Whether the link is a local link relative to a root directory, such as `/docs/setup.html`
+    pub fn is_root_absolute(&self) -> bool {
+        self.is_local() && self.link.starts_with('/')
+    }
+
+    /// Resolves this local link to a path, relatively to the `file` containing it.
+    ///
+    /// Root-absolute links are resolved from `root`, there is no path without it.
+    /// The query and fragment are left out, and the path is percent-decoded.
+    pub fn resolve(&self, file: &Path, root: Option<&Path>) -> Option<PathBuf> {
+        let absolute = |path: &Path| env::current_dir().ok().map(|dir| dir.join(path));
+
+        let (base, link) = match self.link.strip_prefix('/') {
+            Some(link) => (Url::from_directory_path(absolute(root?)?).ok()?, link),
+            None => (
+                Url::from_file_path(absolute(file)?).ok()?,
+                self.link.as_str(),
+            ),
         };
 
-        if response.status().is_success() {
-            LinkStatus::Alive
-        } else {
-            LinkStatus::Dead(response.status().to_string())
+        let mut url = base.join(link).ok()?;
+        url.set_query(None);
+        url.set_fragment(None);
+
+        url.to_file_path().ok()
+    }
+
+    /// Checks that this local link, found in `file`, points to an existing file or directory.
+    ///
+    /// Root-absolute links are resolved from `root`.
+    /// The link is [alive](LinkStatus::Alive) if it exists, [dead](LinkStatus::Dead) otherwise,
+    /// or generates a [warning](LinkStatus::Warn) if it cannot be resolved.
+    pub fn exists(&self, file: &Path, root: Option<&Path>) -> LinkStatus {
+        match self.resolve(file, root) {
+            Some(path) if path.exists() => LinkStatus::Alive(1),
+            Some(_) => LinkStatus::Dead(Failure::new(Reason::Missing, 1)),
+            None => LinkStatus::Warn(Failure::new(Reason::InvalidUrl, 1)),
         }
     }
 
-    /// Formats the link for an OK response
-    pub fn ok(&self, reason: &str) -> String {
-        format!("\t{} {} {}", Green.paint("[OK]"), self, reason)
+    /// Returns the host of the link, if it has one
+    pub fn host(&self) -> Option<String> {
+        Url::parse(&self.link).ok()?.host_str().map(String::from)
     }
 
-    /// Formats the link as a warning response
-    pub fn warn(&self, reason: &str) -> String {
+    /// Sends a single request to the link with `method`, using the given shared `client`.
+    #[cfg(feature = "check")]
+    pub fn request(&self, client: &Client, method: Method) -> reqwest::Result<Response> {
+        method.send(client, self.link.as_str())
+    }
+
+    /// Check if link is alive with `method`, using the given shared `client`.
+    ///
+    /// Returns the status of the link, according to the `accept`ed status codes.
+    /// The link can be [alive](LinkStatus::Alive), [dead](LinkStatus::Dead) or generate a [warning](LinkStatus::Warn).
+    #[cfg(feature = "check")]
+    pub fn alive(&self, client: &Client, method: Method, accept: &Accept) -> LinkStatus {
+        LinkStatus::from_response(&self.request(client, method), 1, accept.codes(self))
+    }
+}
+
+#[cfg(feature = "cli")]
+impl Link {
+    /// Formats the link for the terminal
+    pub fn paint(&self) -> String {
+        Blue.underline().paint(self.as_str()).to_string()
+    }
+
+    /// Formats the link found at `location` for an OK response
+    pub fn ok(&self, location: &Location, reason: &str) -> String {
+        format!(
+            "\t{} {} {} {}",
+            Green.paint("[OK]"),
+            location.paint(),
+            self.paint(),
+            reason
+        )
+    }
+
+    /// Formats the link found at `location` as a warning response
+    pub fn warn(&self, location: &Location, reason: &str) -> String {
         format!(
-            "\t{} {} {}",
+            "\t{} {} {} {}",
             Yellow.paint("[WARN]"),
-            self,
+            location.paint(),
+            self.paint(),
             Yellow.paint(reason)
         )
     }
 
-    /// Formats the link as an error response
-    pub fn err(&self, reason: &str) -> String {
-        format!("\t{} {} {}", Red.paint("[ERR]"), self, Red.paint(reason))
+    /// Formats the link found at `location` as an error response
+    pub fn err(&self, location: &Location, reason: &str) -> String {
+        format!(
+            "\t{} {} {} {}",
+            Red.paint("[ERR]"),
+            location.paint(),
+            self.paint(),
+            Red.paint(reason)
+        )
     }
 
-    /// Formats the link as cached
-    pub fn cache(&self, reason: &str) -> String {
+    /// Formats the link found at `location` as cached
+    pub fn cache(&self, location: &Location, reason: &str) -> String {
         format!(
-            "\t{} {} {}",
+            "\t{} {} {} {}",
             Green.dimmed().paint("[CACHE]"),
-            Style::new().dimmed().paint(format!("{}", self)),
+            location.paint(),
+            Style::new().dimmed().paint(self.paint()),
             Style::new().dimmed().paint(reason)
         )
     }
 
-    /// Formats the link as ignored (i.e. by cli args)
-    pub fn ignored(&self, reason: &str) -> String {
+    /// Formats the link found at `location` as ignored (i.e. by cli args)
+    pub fn ignored(&self, location: &Location, reason: &str) -> String {
         format!(
-            "\t{} {} {}",
+            "\t{} {} {} {}",
             Style::new().dimmed().paint("[IGNORED]"),
-            Style::new().dimmed().paint(format!("{}", self)),
+            location.paint(),
+            Style::new().dimmed().paint(self.paint()),
             Style::new().dimmed().paint(reason)
         )
     }
@@ -117,6 +314,103 @@ impl Link {
 
 impl Display for Link {
     fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        write!(f, "{}", Blue.underline().paint(&self.link))
+        write!(f, "{}", self.link)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn link(s: &str) -> Link {
+        Link::new(&s.to_string())
+    }
+
+    #[test]
+    fn local() {
+        for local in [
+            "./guide.md",
+            "guide.md",
+            "../img/logo.png",
+            "/docs/setup.html",
+            "#top",
+        ] {
+            assert!(link(local).is_local(), "{}", local);
+        }
+        for other in [
+            "https://example.com",
+            "mailto:me@example.com",
+            "//cdn.example.com/app.js",
+        ] {
+            assert!(!link(other).is_local(), "{}", other);
+        }
+
+        assert!(link("HTTPS://example.com").is_remote());
+        assert!(link("http://example.com:99999/").is_remote());
+        assert!(!link("http://example.com:99999/").is_local());
+        assert!(!link("mailto:me@example.com").is_remote());
+
+        assert!(link("/docs/setup.html").is_root_absolute());
+        assert!(!link("docs/setup.html").is_root_absolute());
+    }
+
+    #[test]
+    fn exists() {
+        let file = Path::new("tests_data/index.html");
+
+        for alive in [
+            "README.md",
+            "./README.md?plain=1#title",
+            "../tests_data/hidden/",
+            "#top",
+        ] {
+            assert_eq!(
+                link(alive).exists(file, None),
+                LinkStatus::Alive(1),
+                "{}",
+                alive
+            );
+        }
+
+        assert_eq!(
+            link("./missing.md").exists(file, None),
+            LinkStatus::Dead(Failure::new(Reason::Missing, 1))
+        );
+    }
+
+    #[test]
+    fn exists_root_absolute() {
+        let file = Path::new("tests_data/hidden/.hidden_file");
+
+        assert_eq!(
+            link("/index.html").exists(file, Some(Path::new("tests_data"))),
+            LinkStatus::Alive(1)
+        );
+        assert_eq!(
+            link("/index.html").exists(file, None),
+            LinkStatus::Warn(Failure::new(Reason::InvalidUrl, 1))
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "check")]
+    fn reaccepted() {
+        let accepted = Codes::parse("200..=299,404").unwrap();
+        let not_found = Failure::new(Reason::Status(http::StatusCode::NOT_FOUND), 1);
+        let gone = Failure::new(Reason::Status(http::StatusCode::GONE), 1);
+        let dns = Failure::new(Reason::Dns, 1);
+
+        assert_eq!(
+            LinkStatus::Dead(not_found).reaccepted(&accepted),
+            LinkStatus::Alive(1)
+        );
+        assert_eq!(
+            LinkStatus::Dead(gone.clone()).reaccepted(&accepted),
+            LinkStatus::Dead(gone)
+        );
+        assert_eq!(
+            LinkStatus::Warn(dns.clone()).reaccepted(&accepted),
+            LinkStatus::Warn(dns)
+        );
     }
 }
@@ -0,0 +1,130 @@
+//! Module defining where a link was found.
+
+use std::fmt::{Display, Formatter};
+use std::ops::Range;
+use std::path::{Path, PathBuf};
+
+#[cfg(feature = "cli")]
+use ansi_term::Style;
+
+/// Represents the location of a link in a file
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Location {
+    /// Path to the file containing the link
+    pub file: PathBuf,
+    /// Byte range of the link in the file content
+    pub span: Range<usize>,
+    /// Line of the link, starting at 1
+    pub line: usize,
+    /// Column of the link in characters, starting at 1
+    pub column: usize,
+    /// Line of the end of the link, starting at 1
+    pub end_line: usize,
+    /// Column right after the end of the link in characters, starting at 1
+    pub end_column: usize,
+}
+
+/// Computes locations of byte offsets in a file content
+pub struct Locator<'a> {
+    /// Path to the located file
+    file: &'a Path,
+    /// Content of the file
+    content: &'a str,
+    /// Byte offset of the start of every line
+    lines: Vec<usize>,
+}
+
+impl<'a> Locator<'a> {
+    /// Creates a new locator for the `content` of `file`
+    pub fn new(file: &'a Path, content: &'a str) -> Self {
+        Self {
+            file,
+            content,
+            lines: std::iter::once(0)
+                .chain(content.match_indices('\n').map(|(i, _)| i + 1))
+                .collect(),
+        }
+    }
+
+    /// Computes the location of the byte range `span`
+    pub fn locate(&self, span: Range<usize>) -> Location {
+        let (line, column) = self.position(span.start);
+        let (end_line, end_column) = self.position(span.end);
+
+        Location {
+            file: self.file.to_path_buf(),
+            span,
+            line,
+            column,
+            end_line,
+            end_column,
+        }
+    }
+
+    /// Computes the line and column of the byte `offset`
+    fn position(&self, offset: usize) -> (usize, usize) {
+        let line = self.lines.partition_point(|start| *start <= offset);
+        let start = self.lines[line - 1];
+
+        (line, self.content[start..offset].chars().count() + 1)
+    }
+}
+
+impl Location {
+    /// Formats the line and column for the terminal
+    #[cfg(feature = "cli")]
+    pub fn paint(&self) -> String {
+        Style::new().dimmed().paint(self.to_string()).to_string()
+    }
+}
+
+impl Display for Location {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}:{}", self.line, self.column)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn locate() {
+        let content = "first line\nsecond é line\n\nfourth";
+        let locator = Locator::new(Path::new("file.md"), content);
+
+        let location = |offset| {
+            let location = locator.locate(offset..offset + 1);
+            (location.line, location.column)
+        };
+
+        assert_eq!(location(0), (1, 1));
+        assert_eq!(location(6), (1, 7));
+        assert_eq!(location(11), (2, 1));
+        assert_eq!(location(content.find("line\n\n").unwrap()), (2, 10));
+        assert_eq!(location(content.find("fourth").unwrap()), (4, 1));
+    }
+
+    #[test]
+    fn locate_keeps_span() {
+        let locator = Locator::new(Path::new("file.md"), "some text");
+
+        let location = locator.locate(5..9);
+
+        assert_eq!(location.file, PathBuf::from("file.md"));
+        assert_eq!(location.span, 5..9);
+    }
+
+    #[test]
+    fn locate_end() {
+        let content = "a &amp; [b](c\nd) é";
+        let locator = Locator::new(Path::new("file.md"), content);
+
+        let location = locator.locate(2..7);
+        assert_eq!((location.end_line, location.end_column), (1, 8));
+
+        let location = locator.locate(8..content.len());
+        assert_eq!((location.line, location.column), (1, 9));
+        assert_eq!((location.end_line, location.end_column), (2, 5));
+    }
+}
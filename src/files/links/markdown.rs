@@ -0,0 +1,234 @@
+//! Module extracting links from Markdown documents.
+//!
+//! Documents are parsed as CommonMark, so URLs inside code spans and code blocks can be skipped.
+//! Inline links, images, autolinks and reference definitions are extracted,
+//! as well as URLs written in plain text and links of inline HTML.
+
+use std::ops::Range;
+
+use pulldown_cmark::{Event, LinkType, Options, Parser, Tag};
+
+use crate::files::links::{html, plain};
+
+/// Extracts every link of the Markdown `content`, with its byte span, in order of appearance.
+///
+/// URLs inside code spans and code blocks are only extracted when `code` is set.
+/// Links may be relative.
+pub fn extract(content: &str, code: bool) -> Vec<(String, Range<usize>)> {
+    let options = Options::ENABLE_TABLES
+        | Options::ENABLE_FOOTNOTES
+        | Options::ENABLE_STRIKETHROUGH
+        | Options::ENABLE_TASKLISTS;
+    let parser = Parser::new_ext(content, options).into_offset_iter();
+
+    let mut links: Vec<(String, Range<usize>)> = parser
+        .reference_definitions()
+        .iter()
+        .map(|(_, definition)| {
+            (
+                definition.dest.to_string(),
+                destination(content, definition.span.clone(), &definition.dest),
+            )
+        })
+        .collect();
+
+    // Depth of the links, images and code blocks being walked through
+    let mut nesting = 0;
+    // Span of consecutive plain text, searched for URLs once complete
+    let mut text: Option<Range<usize>> = None;
+    // Span of consecutive HTML, as HTML blocks are split in lines, parsed once complete
+    let mut markup: Option<Range<usize>> = None;
+
+    for (event, range) in parser {
+        if let Event::Text(_) = event {
+            if nesting == 0 {
+                text = match text {
+                    Some(text) if text.end == range.start => Some(text.start..range.end),
+                    Some(text) => {
+                        links.extend(scan(content, text));
+                        Some(range)
+                    }
+                    None => Some(range),
+                };
+            }
+            continue;
+        }
+
+        if let Event::Html(_) = event {
+            markup = match markup {
+                Some(markup) if markup.end == range.start => Some(markup.start..range.end),
+                Some(markup) => {
+                    links.extend(parse(content, markup));
+                    Some(range)
+                }
+                None => Some(range),
+            };
+            continue;
+        }
+
+        if let Some(text) = text.take() {
+            links.extend(scan(content, text));
+        }
+        if let Some(markup) = markup.take() {
+            links.extend(parse(content, markup));
+        }
+
+        match event {
+            Event::Start(Tag::Link(link_type, dest, _) | Tag::Image(link_type, dest, _)) => {
+                nesting += 1;
+
+                if matches!(link_type, LinkType::Inline | LinkType::Autolink) {
+                    links.push((dest.to_string(), destination(content, range, &dest)));
+                }
+            }
+            Event::Start(Tag::CodeBlock(_)) if !code => nesting += 1,
+            Event::End(Tag::CodeBlock(_)) if !code => nesting -= 1,
+            Event::End(Tag::Link(..) | Tag::Image(..)) => nesting -= 1,
+            Event::Code(_) if code && nesting == 0 => links.extend(scan(content, range)),
+            _ => {}
+        }
+    }
+
+    if let Some(text) = text {
+        links.extend(scan(content, text));
+    }
+    if let Some(markup) = markup {
+        links.extend(parse(content, markup));
+    }
+
+    links.sort_by_key(|(_, span)| span.start);
+    links
+}
+
+/// Searches the plain text in `range` for URLs
+fn scan(content: &str, range: Range<usize>) -> impl Iterator<Item = (String, Range<usize>)> {
+    plain::extract(&content[range.clone()])
+        .into_iter()
+        .map(move |(link, span)| (link, range.start + span.start..range.start + span.end))
+}
+
+/// Extracts the links of the HTML in `range`
+fn parse(content: &str, range: Range<usize>) -> impl Iterator<Item = (String, Range<usize>)> {
+    html::extract(&content[range.clone()])
+        .into_iter()
+        .map(move |(link, span)| (link, range.start + span.start..range.start + span.end))
+}
+
+/// Finds the span of the destination `dest` of a link written in `range`.
+///
+/// The destination is searched from the end, after the link text.
+/// When written differently than parsed (escaped characters ...), the whole link is used.
+fn destination(content: &str, range: Range<usize>, dest: &str) -> Range<usize> {
+    match content[range.clone()].rfind(dest) {
+        Some(offset) if !dest.is_empty() => range.start + offset..range.start + offset + dest.len(),
+        _ => range,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn links(content: &str) -> Vec<String> {
+        extract(content, false)
+            .into_iter()
+            .map(|(link, _)| link)
+            .collect()
+    }
+
+    #[test]
+    fn inline_links_and_images() {
+        let content =
+            "See [the docs](https://example.com/docs) and ![logo](img/logo.png \"Logo\").";
+
+        assert_eq!(links(content), ["https://example.com/docs", "img/logo.png"]);
+    }
+
+    #[test]
+    fn reference_links() {
+        let content = "Read [the guide][guide] and [faq].\n\n[guide]: https://example.com/guide\n[faq]: <https://example.com/faq> \"FAQ\"\n";
+
+        assert_eq!(
+            links(content),
+            ["https://example.com/guide", "https://example.com/faq"]
+        );
+    }
+
+    #[test]
+    fn autolinks_and_plain_urls() {
+        let content = "Visit <https://example.com/auto> or https://example.com/plain.\n(see https://example.com/paren)";
+
+        assert_eq!(
+            links(content),
+            [
+                "https://example.com/auto",
+                "https://example.com/plain",
+                "https://example.com/paren"
+            ]
+        );
+    }
+
+    #[test]
+    fn skips_code() {
+        let content = "Run `curl https://example.com/span`.\n\n```sh\ncurl https://example.com/block\n```\n\n    https://example.com/indented\n";
+
+        assert!(links(content).is_empty());
+    }
+
+    #[test]
+    fn includes_code() {
+        let content = "Run `curl https://example.com/span`.\n\n```sh\ncurl https://example.com/block\n```\n\n    https://example.com/indented\n";
+
+        let links: Vec<String> = extract(content, true)
+            .into_iter()
+            .map(|(link, span)| {
+                assert_eq!(&content[span], link);
+                link
+            })
+            .collect();
+
+        assert_eq!(
+            links,
+            [
+                "https://example.com/span",
+                "https://example.com/block",
+                "https://example.com/indented"
+            ]
+        );
+    }
+
+    #[test]
+    fn link_text_not_scanned() {
+        assert_eq!(
+            links("[https://example.com](https://example.com/target)"),
+            ["https://example.com/target"]
+        );
+    }
+
+    #[test]
+    fn inline_html() {
+        assert_eq!(
+            links("Some <a href=\"https://example.com/html\">HTML</a> link"),
+            ["https://example.com/html"]
+        );
+    }
+
+    #[test]
+    fn multiline_html() {
+        let content = "<div>\n<a\n href=\"https://example.com/multi\">x</a>\n</div>\n";
+
+        for (link, span) in extract(content, false) {
+            assert_eq!(&content[span], link);
+        }
+        assert_eq!(links(content), ["https://example.com/multi"]);
+    }
+
+    #[test]
+    fn spans() {
+        let content = "Text\n[link](https://example.com/a_b) and https://example.com/c_d_e.";
+
+        for (link, span) in extract(content, false) {
+            assert_eq!(&content[span], link);
+        }
+    }
+}
@@ -0,0 +1,119 @@
+//! Module defining how links are requested.
+
+use std::fmt::{Display, Formatter};
+
+#[cfg(feature = "check")]
+use http::StatusCode;
+#[cfg(feature = "check")]
+use reqwest::blocking::{Client, Response};
+
+/// Represents the HTTP method used to check links
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Method {
+    /// Sends a `HEAD` request first, falling back to `GET` if the server does not support it
+    #[default]
+    Head,
+    /// Only sends `GET` requests
+    Get,
+}
+
+impl Method {
+    /// Names of every method, as accepted by cli arguments
+    pub const NAMES: [&'static str; 2] = ["head", "get"];
+
+    /// Finds the method named `name`, one of [`Method::NAMES`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "head" => Some(Method::Head),
+            "get" => Some(Method::Get),
+            _ => None,
+        }
+    }
+
+    /// Sends a request to `url` with `client`.
+    ///
+    /// `GET` responses are returned as soon as headers are received,
+    /// the body is never downloaded.
+    #[cfg(feature = "check")]
+    pub fn send(&self, client: &Client, url: &str) -> reqwest::Result<Response> {
+        match self {
+            Method::Head => {
+                let response = client.head(url).send();
+
+                if Self::head_unsupported(&response) {
+                    client.get(url).send()
+                } else {
+                    response
+                }
+            }
+            Method::Get => client.get(url).send(),
+        }
+    }
+
+    /// Whether the server does not support or misbehaves on `HEAD` requests, according to its `response`
+    #[cfg(feature = "check")]
+    fn head_unsupported(response: &reqwest::Result<Response>) -> bool {
+        match response {
+            Ok(r) => matches!(
+                r.status(),
+                StatusCode::METHOD_NOT_ALLOWED | StatusCode::NOT_IMPLEMENTED
+            ),
+            Err(e) => !e.is_timeout() && !e.is_builder() && !e.is_redirect() && !e.is_connect(),
+        }
+    }
+}
+
+impl Display for Method {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Method::Head => write!(f, "head"),
+            Method::Get => write!(f, "get"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    #[cfg(feature = "check")]
+    use crate::files::links::accept::Accept;
+    #[cfg(feature = "check")]
+    use crate::files::links::link::{Link, LinkStatus};
+    #[cfg(feature = "check")]
+    use crate::files::links::server::Server;
+
+    use super::*;
+
+    #[test]
+    fn names() {
+        for name in Method::NAMES {
+            assert_eq!(Method::from_name(name).unwrap().to_string(), name);
+        }
+    }
+
+    #[test]
+    fn unknown_name() {
+        assert_eq!(Method::from_name("post"), None);
+    }
+
+    #[test]
+    #[cfg(feature = "check")]
+    fn head_falls_back_to_get() {
+        let server = Server::start(|request| {
+            if request.starts_with("HEAD") {
+                String::from("405 Method Not Allowed")
+            } else {
+                String::from("200 OK")
+            }
+        });
+        let link = Link::new(&server.url("/page"));
+
+        assert_eq!(
+            link.alive(&Client::new(), Method::Head, &Accept::default()),
+            LinkStatus::Alive(1)
+        );
+        assert_eq!(
+            server.requests(),
+            ["HEAD /page HTTP/1.1", "GET /page HTTP/1.1"]
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Module defining which failure reasons make a link dead.
+//!
+//! By default, only unexpected status codes and missing local files make a link dead,
+//! other failures are reported as warnings.
+
+use std::collections::HashSet;
+
+use crate::files::links::link::LinkStatus;
+
+/// Represents the mapping of failure reasons to dead links or warnings
+pub struct Policy {
+    /// Names of the reasons making a link dead
+    dead: HashSet<String>,
+}
+
+impl Default for Policy {
+    fn default() -> Self {
+        Self::new([].iter(), [].iter())
+    }
+}
+
+impl Policy {
+    /// Creates a new policy where reasons named in `dead` make a link dead,
+    /// and reasons named in `warn` only generate a warning.
+    ///
+    /// `warn` takes precedence over `dead`.
+    /// Reasons named in neither generate a warning, except unexpected status codes and missing local files.
+    pub fn new<'a>(
+        dead: impl Iterator<Item = &'a String>,
+        warn: impl Iterator<Item = &'a String>,
+    ) -> Self {
+        let mut dead: HashSet<String> = dead.cloned().collect();
+        dead.insert(String::from("status"));
+        dead.insert(String::from("missing"));
+
+        for reason in warn {
+            dead.remove(reason);
+        }
+
+        Self { dead }
+    }
+
+    /// Applies the policy to `status`, turning failures into dead links or warnings.
+    ///
+    /// Other statuses are left untouched.
+    pub fn apply(&self, status: LinkStatus) -> LinkStatus {
+        match status {
+            LinkStatus::Dead(failure) | LinkStatus::Warn(failure) => {
+                if self.dead.contains(failure.reason.name()) {
+                    LinkStatus::Dead(failure)
+                } else {
+                    LinkStatus::Warn(failure)
+                }
+            }
+            status => status,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use http::StatusCode;
+
+    use crate::files::links::link::Failure;
+    use crate::files::links::reason::Reason;
+
+    use super::*;
+
+    fn names(names: &[&str]) -> Vec<String> {
+        names.iter().map(|name| name.to_string()).collect()
+    }
+
+    #[test]
+    fn default_status_dead() {
+        let failure = Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1);
+
+        assert_eq!(
+            Policy::default().apply(LinkStatus::Warn(failure.clone())),
+            LinkStatus::Dead(failure)
+        );
+    }
+
+    #[test]
+    fn default_missing_dead() {
+        let failure = Failure::new(Reason::Missing, 1);
+
+        assert_eq!(
+            Policy::default().apply(LinkStatus::Warn(failure.clone())),
+            LinkStatus::Dead(failure)
+        );
+    }
+
+    #[test]
+    fn default_errors_warn() {
+        let failure = Failure::new(Reason::Dns, 1);
+
+        assert_eq!(
+            Policy::default().apply(LinkStatus::Dead(failure.clone())),
+            LinkStatus::Warn(failure)
+        );
+    }
+
+    #[test]
+    fn warn_takes_precedence() {
+        let policy = Policy::new(names(&["dns", "tls"]).iter(), names(&["tls"]).iter());
+
+        assert!(matches!(
+            policy.apply(LinkStatus::Warn(Failure::new(Reason::Dns, 1))),
+            LinkStatus::Dead(_)
+        ));
+        assert!(matches!(
+            policy.apply(LinkStatus::Dead(Failure::new(Reason::Tls, 1))),
+            LinkStatus::Warn(_)
+        ));
+    }
+
+    #[test]
+    fn other_statuses_untouched() {
+        assert_eq!(
+            Policy::default().apply(LinkStatus::Alive(1)),
+            LinkStatus::Alive(1)
+        );
+        assert_eq!(
+            Policy::default().apply(LinkStatus::Ignored),
+            LinkStatus::Ignored
+        );
+    }
+}
@@ -0,0 +1,214 @@
+//! Module defining the worker pool used to check links concurrently.
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::mpsc;
+use std::thread;
+use std::thread::sleep;
+
+use reqwest::blocking::Client;
+
+use crate::files::links::accept::Accept;
+use crate::files::links::limiter::Limiter;
+use crate::files::links::link::{Link, LinkStatus};
+use crate::files::links::method::Method;
+use crate::files::links::retry::Retry;
+
+/// Represents a pool of workers checking links concurrently
+pub struct Pool {
+    /// Maximum number of links checked at the same time
+    jobs: usize,
+    /// Per host request budget applied to every check
+    limiter: Limiter,
+    /// Retry policy of transient failures
+    retry: Retry,
+    /// HTTP method used to request links
+    method: Method,
+    /// Status codes making a link alive
+    accept: Accept,
+}
+
+impl Pool {
+    /// Creates a new pool running at most `jobs` checks at the same time,
+    /// within the per host budget of `limiter` and retrying transient failures according to `retry`.
+    /// Links are requested with `method` and alive when answering an `accept`ed status code.
+    ///
+    /// A pool always has at least one worker.
+    pub fn new(
+        jobs: usize,
+        limiter: Limiter,
+        retry: Retry,
+        method: Method,
+        accept: Accept,
+    ) -> Self {
+        Self {
+            jobs: jobs.max(1),
+            limiter,
+            retry,
+            method,
+            accept,
+        }
+    }
+
+    /// Check every link in `links` concurrently.
+    ///
+    /// Each worker picks the next unchecked link until there are none left.
+    /// Every worker shares the same `client`, so connections are reused.
+    /// Workers wait for the link's host budget before sending every request.
+    /// `on_checked` is called on the calling thread with the status of each link, as soon as it is checked.
+    pub fn check(
+        &self,
+        client: &Client,
+        links: &[Link],
+        on_checked: &mut dyn FnMut(&Link, &LinkStatus),
+    ) {
+        let next = &AtomicUsize::new(0);
+        let (sender, receiver) = mpsc::channel();
+
+        thread::scope(|scope| {
+            for _ in 0..self.jobs.min(links.len()) {
+                let sender = sender.clone();
+
+                scope.spawn(move || {
+                    while let Some(link) = links.get(next.fetch_add(1, Ordering::Relaxed)) {
+                        sender
+                            .send((link, self.check_one(client, link)))
+                            .expect("Checked links receiver should outlive workers");
+                    }
+                });
+            }
+
+            drop(sender);
+
+            for (link, status) in receiver {
+                on_checked(link, &status);
+            }
+        });
+    }
+
+    /// Check a single link, retrying transient failures.
+    fn check_one(&self, client: &Client, link: &Link) -> LinkStatus {
+        let mut attempts = 1;
+
+        loop {
+            let response = {
+                let _permit = self.limiter.acquire(link);
+                link.request(client, self.method)
+            };
+
+            match self.retry.delay(attempts, &response) {
+                Some(delay) => {
+                    drop(response);
+                    sleep(delay);
+                    attempts += 1;
+                }
+                None => {
+                    return LinkStatus::from_response(&response, attempts, self.accept.codes(link))
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::sync::atomic::AtomicU32;
+    use std::sync::Arc;
+    use std::time::{Duration, Instant};
+
+    use crate::files::links::server::Server;
+
+    use super::*;
+
+    #[test]
+    fn at_least_one_job() {
+        let pool = Pool::new(
+            0,
+            Limiter::default(),
+            Retry::default(),
+            Method::Head,
+            Accept::default(),
+        );
+
+        assert_eq!(pool.jobs, 1);
+    }
+
+    #[test]
+    fn check_nothing() {
+        let pool = Pool::new(
+            4,
+            Limiter::default(),
+            Retry::default(),
+            Method::Head,
+            Accept::default(),
+        );
+
+        let mut checked = 0;
+
+        pool.check(&Client::new(), &[], &mut |_, _| checked += 1);
+
+        assert_eq!(checked, 0);
+    }
+
+    #[test]
+    fn checks_concurrently() {
+        let in_flight = Arc::new(AtomicUsize::new(0));
+        let max_in_flight = Arc::new(AtomicUsize::new(0));
+        let server = {
+            let in_flight = in_flight.clone();
+            let max_in_flight = max_in_flight.clone();
+
+            Server::start(move |_| {
+                let current = in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+                max_in_flight.fetch_max(current, Ordering::SeqCst);
+                sleep(Duration::from_millis(200));
+                in_flight.fetch_sub(1, Ordering::SeqCst);
+
+                String::from("200 OK")
+            })
+        };
+        let pool = Pool::new(
+            4,
+            Limiter::default(),
+            Retry::default(),
+            Method::Get,
+            Accept::default(),
+        );
+        let links: Vec<Link> = (0..4)
+            .map(|i| Link::new(&server.url(&format!("/{}", i))))
+            .collect();
+        let mut checked = Vec::new();
+
+        pool.check(&Client::new(), &links, &mut |link, status| {
+            checked.push((link.clone(), status.clone()));
+        });
+
+        assert_eq!(checked.len(), 4);
+        assert!(checked
+            .iter()
+            .all(|(_, status)| *status == LinkStatus::Alive(1)));
+        assert!(max_in_flight.load(Ordering::SeqCst) > 1);
+    }
+
+    #[test]
+    fn retries_after_server_delay() {
+        let requests = AtomicU32::new(0);
+        let server = Server::start(move |_| match requests.fetch_add(1, Ordering::Relaxed) {
+            0 | 1 => String::from("503 Service Unavailable\r\nRetry-After: 0"),
+            _ => String::from("200 OK"),
+        });
+        // The backoff is long enough to notice if `Retry-After` was not followed
+        let pool = Pool::new(
+            1,
+            Limiter::default(),
+            Retry::new(3, Duration::from_secs(60)),
+            Method::Get,
+            Accept::default(),
+        );
+        let link = Link::new(&server.url("/flaky"));
+        let start = Instant::now();
+
+        assert_eq!(pool.check_one(&Client::new(), &link), LinkStatus::Alive(3));
+        assert!(start.elapsed() < Duration::from_secs(10));
+        assert_eq!(server.requests().len(), 3);
+    }
+}
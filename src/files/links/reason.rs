@@ -0,0 +1,198 @@
+//! Module defining why a link is not alive.
+//!
+//! Reasons are structured, so they can be reported individually
+//! and mapped to a warning or an error by a [`Policy`](crate::files::links::policy::Policy).
+
+#[cfg(feature = "check")]
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+#[cfg(feature = "check")]
+use std::io;
+
+use http::StatusCode;
+
+#[cfg(feature = "check")]
+use crate::files::links::resolver::Unresolved;
+
+/// Represents the reason a link is not alive
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub enum Reason {
+    /// The server answered with an unexpected status code
+    Status(StatusCode),
+    /// The host name could not be resolved
+    Dns,
+    /// The connection to the server failed
+    #[default]
+    Connect,
+    /// The TLS handshake failed (invalid certificate ...)
+    Tls,
+    /// The server took too long to answer
+    Timeout,
+    /// The server redirected too many times
+    RedirectLoop,
+    /// The link is not a valid URL
+    InvalidUrl,
+    /// The response body could not be read
+    Body,
+    /// The local file or directory does not exist
+    Missing,
+}
+
+impl Reason {
+    /// Names of every reason kind, as accepted by cli arguments
+    pub const NAMES: [&'static str; 9] = [
+        "status",
+        "dns",
+        "connect",
+        "tls",
+        "timeout",
+        "redirect",
+        "invalid-url",
+        "body",
+        "missing",
+    ];
+
+    /// Determines the reason of a request error.
+    ///
+    /// DNS failures are only recognized for clients built by [`CheckerConfig::client`](crate::checker::CheckerConfig::client).
+    #[cfg(feature = "check")]
+    pub fn from_error(error: &reqwest::Error) -> Self {
+        if error.is_timeout() {
+            Reason::Timeout
+        } else if error.is_redirect() {
+            Reason::RedirectLoop
+        } else if error.is_builder() {
+            Reason::InvalidUrl
+        } else if error.is_body() || error.is_decode() {
+            Reason::Body
+        } else if Self::sources(error).any(Self::invalid_data) {
+            Reason::Tls
+        } else if Self::sources(error).any(|e| e.is::<Unresolved>()) {
+            Reason::Dns
+        } else {
+            Reason::Connect
+        }
+    }
+
+    /// Finds the reason named `name`, one of [`Reason::NAMES`].
+    ///
+    /// Status reasons have no single name, `None` is returned for them.
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "dns" => Some(Reason::Dns),
+            "connect" => Some(Reason::Connect),
+            "tls" => Some(Reason::Tls),
+            "timeout" => Some(Reason::Timeout),
+            "redirect" => Some(Reason::RedirectLoop),
+            "invalid-url" => Some(Reason::InvalidUrl),
+            "body" => Some(Reason::Body),
+            "missing" => Some(Reason::Missing),
+            _ => None,
+        }
+    }
+
+    /// Name of this reason kind, one of [`Reason::NAMES`]
+    pub fn name(&self) -> &'static str {
+        match self {
+            Reason::Status(_) => "status",
+            Reason::Dns => "dns",
+            Reason::Connect => "connect",
+            Reason::Tls => "tls",
+            Reason::Timeout => "timeout",
+            Reason::RedirectLoop => "redirect",
+            Reason::InvalidUrl => "invalid-url",
+            Reason::Body => "body",
+            Reason::Missing => "missing",
+        }
+    }
+
+    /// Whether `error` is an IO error caused by invalid data, as raised by failed TLS handshakes
+    #[cfg(feature = "check")]
+    fn invalid_data(error: &(dyn Error + 'static)) -> bool {
+        match error.downcast_ref::<io::Error>() {
+            Some(e) => {
+                e.kind() == io::ErrorKind::InvalidData
+                    || e.get_ref().map_or(false, |inner| Self::invalid_data(inner))
+            }
+            None => false,
+        }
+    }
+
+    /// Iterates over the sources of `error`, `error` excluded
+    #[cfg(feature = "check")]
+    fn sources<'a>(
+        error: &'a (dyn Error + 'static),
+    ) -> impl Iterator<Item = &'a (dyn Error + 'static)> {
+        std::iter::successors(error.source(), |e| (*e).source())
+    }
+}
+
+impl Display for Reason {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Reason::Status(status) => write!(f, "{}", status),
+            Reason::Dns => write!(f, "Host not found"),
+            Reason::Connect => write!(f, "Connection failed"),
+            Reason::Tls => write!(f, "TLS error"),
+            Reason::Timeout => write!(f, "Timed out"),
+            Reason::RedirectLoop => write!(f, "Too many redirections"),
+            Reason::InvalidUrl => write!(f, "Invalid URL"),
+            Reason::Body => write!(f, "Invalid response body"),
+            Reason::Missing => write!(f, "File not found"),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "check")]
+    use crate::checker::CheckerBuilder;
+
+    #[cfg(feature = "check")]
+    fn reason(url: &str) -> Reason {
+        let client = CheckerBuilder::default().build().unwrap().config().client();
+
+        Reason::from_error(&client.get(url).send().unwrap_err())
+    }
+
+    #[test]
+    #[cfg(feature = "check")]
+    fn invalid_url() {
+        assert_eq!(reason("not a url"), Reason::InvalidUrl);
+    }
+
+    #[test]
+    #[cfg(feature = "check")]
+    fn connect() {
+        assert_eq!(reason("http://127.0.0.1:1"), Reason::Connect);
+    }
+
+    #[test]
+    #[cfg(feature = "check")]
+    fn dns() {
+        assert_eq!(reason("http://deadlinkrs.invalid"), Reason::Dns);
+    }
+
+    #[test]
+    fn names() {
+        let reasons = [
+            Reason::Status(StatusCode::NOT_FOUND),
+            Reason::Dns,
+            Reason::Connect,
+            Reason::Tls,
+            Reason::Timeout,
+            Reason::RedirectLoop,
+            Reason::InvalidUrl,
+            Reason::Body,
+        ];
+
+        for (reason, name) in reasons.iter().zip(Reason::NAMES) {
+            assert_eq!(reason.name(), name);
+        }
+
+        for reason in &reasons[1..] {
+            assert_eq!(Reason::from_name(reason.name()).as_ref(), Some(reason));
+        }
+    }
+}
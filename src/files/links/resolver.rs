@@ -0,0 +1,64 @@
+//! Module defining how host names are resolved.
+//!
+//! Host names are looked up by the system resolver, as the HTTP client does by default,
+//! but lookup failures are raised as an [`Unresolved`] error,
+//! so they can be told apart from other connection failures without a second lookup.
+
+use std::error::Error;
+use std::fmt::{Display, Formatter};
+use std::io;
+
+use hyper::client::connect::dns::{GaiResolver, Name};
+use hyper::service::Service;
+use reqwest::dns::{Addrs, Resolve, Resolving};
+
+/// Represents a failed host name lookup
+#[derive(Debug)]
+pub struct Unresolved(io::Error);
+
+impl Display for Unresolved {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "host not found: {}", self.0)
+    }
+}
+
+impl Error for Unresolved {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+/// Resolves host names with the system resolver, failing with [`Unresolved`]
+#[derive(Clone)]
+pub struct Resolver {
+    /// System resolver, running lookups in a thread pool
+    inner: GaiResolver,
+}
+
+impl Default for Resolver {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Resolver {
+    /// Creates a new resolver
+    pub fn new() -> Self {
+        Self {
+            inner: GaiResolver::new(),
+        }
+    }
+}
+
+impl Resolve for Resolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        let lookup = self.inner.clone().call(name);
+
+        Box::pin(async move {
+            match lookup.await {
+                Ok(addrs) => Ok(Box::new(addrs) as Addrs),
+                Err(e) => Err(Box::new(Unresolved(e)) as Box<dyn Error + Send + Sync>),
+            }
+        })
+    }
+}
@@ -1,10 +1,17 @@
 //! The results module groups data related to check results.
+//!
+//! Results can be iterated over, filtered by status and grouped by file, URL or host.
 
-use std::fmt::{Display, Formatter};
+use std::collections::HashMap;
+use std::hash::Hash;
 use std::mem::discriminant;
+use std::path::Path;
 
-use crate::files::links::link::LinkStatus::{Alive, Cached, Dead, Ignored, Warn};
 use crate::files::links::link::{Link, LinkStatus};
+use crate::files::links::location::Location;
+
+/// A single result: the link, its status and where it was found
+pub type Entry<'a> = (&'a Link, &'a LinkStatus, &'a Location);
 
 /// Represents the results links check
 pub struct Results {
@@ -14,6 +21,10 @@ pub struct Results {
     ///
     /// Correspondence with `keys` is kept by index.
     values: Vec<LinkStatus>,
+    /// Where each link was found.
+    ///
+    /// Correspondence with `keys` is kept by index.
+    locations: Vec<Location>,
 }
 
 impl Default for Results {
@@ -28,6 +39,7 @@ impl Results {
         Self {
             keys: Vec::new(),
             values: Vec::new(),
+            locations: Vec::new(),
         }
     }
 
@@ -37,50 +49,106 @@ impl Results {
     pub fn merge(&mut self, mut other: Self) {
         self.keys.append(&mut other.keys);
         self.values.append(&mut other.values);
+        self.locations.append(&mut other.locations);
     }
 
-    /// Inserts a new result
-    pub fn inserts(&mut self, link: &Link, status: LinkStatus) {
+    /// Inserts a new result for `link` found at `location`
+    pub fn inserts(&mut self, link: &Link, location: &Location, status: LinkStatus) {
         self.keys.push(link.clone());
         self.values.push(status);
+        self.locations.push(location.clone());
+    }
+
+    /// Number of results
+    pub fn len(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Whether there is no result at all
+    pub fn is_empty(&self) -> bool {
+        self.keys.is_empty()
+    }
+
+    /// Iterates over every result, in insertion order
+    pub fn iter(&self) -> impl Iterator<Item = Entry<'_>> {
+        self.keys
+            .iter()
+            .zip(self.values.iter())
+            .zip(self.locations.iter())
+            .map(|((link, status), location)| (link, status, location))
+    }
+
+    /// Iterates over results with the given status, in insertion order.
+    ///
+    /// Only enum discriminant is taken into account.
+    pub fn with_status(&self, status: LinkStatus) -> impl Iterator<Item = Entry<'_>> {
+        self.iter()
+            .filter(move |(_, s, _)| discriminant(*s) == discriminant(&status))
     }
 
     /// Count the number of results with the given status.
     ///
     /// Only enum discriminant is taken into account.
     pub fn count_with(&self, status: LinkStatus) -> usize {
-        self.values
-            .clone()
-            .into_iter()
-            .filter(|s| discriminant(s) == discriminant(&status))
-            .count()
+        self.with_status(status).count()
     }
-}
 
-impl Display for Results {
-    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
-        let mut s = "".to_string();
-
-        for (i, key) in self.keys.iter().enumerate() {
-            s = format!(
-                "{}\n{}",
-                s,
-                match self.values.get(i).unwrap() {
-                    Alive => key.ok(""),
-                    Dead(r) => key.err(r),
-                    Warn(r) => key.warn(r),
-                    Cached => key.cache(""),
-                    Ignored => key.ignored(""),
+    /// Groups results by the file they were found in, in order of first appearance
+    pub fn by_file(&self) -> Vec<(&Path, Vec<Entry<'_>>)> {
+        self.group_by(|(_, _, location)| location.file.as_path())
+    }
+
+    /// Groups results by URL, in order of first appearance
+    pub fn by_url(&self) -> Vec<(&Link, Vec<Entry<'_>>)> {
+        self.group_by(|(link, _, _)| *link)
+    }
+
+    /// Groups results by host, in order of first appearance.
+    ///
+    /// Links without a host, such as `mailto:` links, are grouped under `None`.
+    pub fn by_host(&self) -> Vec<(Option<String>, Vec<Entry<'_>>)> {
+        self.group_by(|(link, _, _)| link.host())
+    }
+
+    /// Groups results by the key computed by `key`, in order of first appearance
+    fn group_by<'a, K: Eq + Hash + Clone>(
+        &'a self,
+        key: impl Fn(&Entry<'a>) -> K,
+    ) -> Vec<(K, Vec<Entry<'a>>)> {
+        let mut indexes: HashMap<K, usize> = HashMap::new();
+        let mut groups: Vec<(K, Vec<Entry>)> = Vec::new();
+
+        for entry in self.iter() {
+            let key = key(&entry);
+
+            match indexes.get(&key) {
+                Some(&i) => groups[i].1.push(entry),
+                None => {
+                    indexes.insert(key.clone(), groups.len());
+                    groups.push((key, vec![entry]));
                 }
-            );
+            }
         }
 
-        write!(f, "{}", s)
+        groups
+    }
+}
+
+impl<'a> IntoIterator for &'a Results {
+    type Item = Entry<'a>;
+    type IntoIter = Box<dyn Iterator<Item = Entry<'a>> + 'a>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        Box::new(self.iter())
     }
 }
 
 #[cfg(test)]
 mod tests {
+    use crate::files::links::link::Failure;
+    use crate::files::links::link::LinkStatus::{Alive, Cached, Dead, Ignored, Warn};
+    use crate::files::links::reason::Reason;
+
     use super::*;
 
     #[test]
@@ -96,13 +164,13 @@ mod tests {
         let mut results = Results::new();
         let link = Link::new(&"https://example.com".to_string());
 
-        results.inserts(&link, Alive);
+        results.inserts(&link, &Location::default(), Alive(1));
 
         assert_eq!(results.keys.len(), 1);
         assert_eq!(results.values.len(), 1);
 
         assert_eq!(results.keys.first().unwrap(), &link);
-        assert_eq!(results.values.first().unwrap(), &Alive);
+        assert_eq!(results.values.first().unwrap(), &Alive(1));
     }
 
     #[test]
@@ -114,8 +182,8 @@ mod tests {
         let link = Link::new(&"https://example.com".to_string());
         let link2 = Link::new(&"https://example.com/2".to_string());
 
-        results1.inserts(&link, Alive);
-        results2.inserts(&link2, Ignored);
+        results1.inserts(&link, &Location::default(), Alive(1));
+        results2.inserts(&link2, &Location::default(), Ignored);
 
         // Merge
         results1.merge(results2);
@@ -134,9 +202,9 @@ mod tests {
         let mut results = Results::new();
         let link = Link::new(&"https://example.com".to_string());
 
-        results.inserts(&link, Alive);
+        results.inserts(&link, &Location::default(), Alive(1));
 
-        assert_eq!(results.count_with(Alive), 1);
+        assert_eq!(results.count_with(Alive(1)), 1);
         assert_eq!(results.count_with(Ignored), 0);
     }
 
@@ -146,8 +214,103 @@ mod tests {
         let mut results = Results::new();
         let link = Link::new(&"https://example.com".to_string());
 
-        results.inserts(&link, Warn("Too many redirection".to_string()));
+        results.inserts(
+            &link,
+            &Location::default(),
+            Warn(Failure::new(Reason::RedirectLoop, 1)),
+        );
+
+        assert_eq!(results.count_with(Warn(Failure::default())), 1);
+    }
+
+    fn grouped() -> Results {
+        let mut results = Results::new();
+        let location = |file: &str| Location {
+            file: file.into(),
+            ..Location::default()
+        };
+        let link = |url: &str| Link::new(&url.to_string());
+
+        results.inserts(&link("https://example.com"), &location("a.md"), Alive(1));
+        results.inserts(
+            &link("mailto:someone@example.com"),
+            &location("b.md"),
+            Ignored,
+        );
+        results.inserts(
+            &link("https://docs.example.com"),
+            &location("a.md"),
+            Dead(Failure::new(Reason::Dns, 1)),
+        );
+        results.inserts(&link("https://example.com"), &location("b.md"), Cached);
+
+        results
+    }
+
+    #[test]
+    fn with_status() {
+        let results = grouped();
+
+        let alive: Vec<_> = results
+            .with_status(Alive(1))
+            .map(|(l, _, _)| l.as_str())
+            .collect();
+
+        assert_eq!(alive, ["https://example.com"]);
+        assert_eq!(results.with_status(Dead(Failure::default())).count(), 1);
+        assert_eq!(results.with_status(Warn(Failure::default())).count(), 0);
+    }
+
+    #[test]
+    fn by_file() {
+        let results = grouped();
+
+        let files: Vec<_> = results
+            .by_file()
+            .into_iter()
+            .map(|(file, entries)| (file.to_path_buf(), entries.len()))
+            .collect();
+
+        assert_eq!(files, [("a.md".into(), 2), ("b.md".into(), 2)]);
+    }
+
+    #[test]
+    fn by_url() {
+        let results = grouped();
+
+        let urls: Vec<_> = results
+            .by_url()
+            .into_iter()
+            .map(|(link, entries)| (link.as_str(), entries.len()))
+            .collect();
+
+        assert_eq!(
+            urls,
+            [
+                ("https://example.com", 2),
+                ("mailto:someone@example.com", 1),
+                ("https://docs.example.com", 1)
+            ]
+        );
+    }
+
+    #[test]
+    fn by_host() {
+        let results = grouped();
+
+        let hosts: Vec<_> = results
+            .by_host()
+            .into_iter()
+            .map(|(host, entries)| (host, entries.len()))
+            .collect();
 
-        assert_eq!(results.count_with(Warn("".to_string())), 1);
+        assert_eq!(
+            hosts,
+            [
+                (Some("example.com".to_string()), 2),
+                (None, 1),
+                (Some("docs.example.com".to_string()), 1)
+            ]
+        );
     }
 }
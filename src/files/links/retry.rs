@@ -0,0 +1,126 @@
+//! Module defining the retry policy of link checks.
+//!
+//! Network errors, `429 Too Many Requests` and `5xx` responses are considered transient
+//! and retried with an exponential backoff, unless the server sends a `Retry-After` header.
+
+use std::time::{Duration, SystemTime};
+
+use http::header::RETRY_AFTER;
+use http::StatusCode;
+use reqwest::blocking::Response;
+
+/// Longest delay ever waited between two attempts, whatever the server asks for
+const MAX_DELAY: Duration = Duration::from_secs(60);
+
+/// Represents how transient failures are retried
+pub struct Retry {
+    /// Maximum number of retries after the first attempt
+    retries: u32,
+    /// Delay before the first retry, doubled on every following retry
+    backoff: Duration,
+}
+
+impl Default for Retry {
+    fn default() -> Self {
+        Self::new(0, Duration::from_millis(500))
+    }
+}
+
+impl Retry {
+    /// Creates a new retry policy retrying at most `retries` times,
+    /// waiting `backoff` before the first retry.
+    pub fn new(retries: u32, backoff: Duration) -> Self {
+        Self { retries, backoff }
+    }
+
+    /// Computes the delay to wait after `attempt` got `response`.
+    ///
+    /// Returns `None` when `response` is final, i.e. it is not transient or there are no retries left.
+    pub fn delay(&self, attempt: u32, response: &reqwest::Result<Response>) -> Option<Duration> {
+        if attempt > self.retries || !Self::transient(response) {
+            return None;
+        }
+
+        let delay = response
+            .as_ref()
+            .ok()
+            .and_then(|r| r.headers().get(RETRY_AFTER))
+            .and_then(|value| value.to_str().ok())
+            .and_then(|value| Self::retry_after(value, SystemTime::now()))
+            .unwrap_or_else(|| self.backoff(attempt));
+
+        Some(delay.min(MAX_DELAY))
+    }
+
+    /// Exponential backoff to wait after `attempt`
+    fn backoff(&self, attempt: u32) -> Duration {
+        self.backoff
+            .saturating_mul(2u32.saturating_pow(attempt.saturating_sub(1)))
+    }
+
+    /// Whether `response` is worth retrying
+    fn transient(response: &reqwest::Result<Response>) -> bool {
+        match response {
+            Ok(r) => r.status() == StatusCode::TOO_MANY_REQUESTS || r.status().is_server_error(),
+            Err(e) => !e.is_builder() && !e.is_redirect(),
+        }
+    }
+
+    /// Parses a `Retry-After` header `value`, either a number of seconds or an HTTP date.
+    fn retry_after(value: &str, now: SystemTime) -> Option<Duration> {
+        if let Ok(seconds) = value.trim().parse::<u64>() {
+            return Some(Duration::from_secs(seconds));
+        }
+
+        httpdate::parse_http_date(value.trim())
+            .ok()
+            .map(|date| date.duration_since(now).unwrap_or_default())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backoff_doubles() {
+        let retry = Retry::new(3, Duration::from_millis(100));
+
+        assert_eq!(retry.backoff(1), Duration::from_millis(100));
+        assert_eq!(retry.backoff(2), Duration::from_millis(200));
+        assert_eq!(retry.backoff(3), Duration::from_millis(400));
+    }
+
+    #[test]
+    fn retry_after_seconds() {
+        assert_eq!(
+            Retry::retry_after("120", SystemTime::now()),
+            Some(Duration::from_secs(120))
+        );
+    }
+
+    #[test]
+    fn retry_after_date() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+        assert_eq!(
+            Retry::retry_after("Wed, 21 Oct 2015 07:28:30 GMT", now),
+            Some(Duration::from_secs(30))
+        );
+    }
+
+    #[test]
+    fn retry_after_past_date() {
+        let now = httpdate::parse_http_date("Wed, 21 Oct 2015 07:28:00 GMT").unwrap();
+
+        assert_eq!(
+            Retry::retry_after("Wed, 21 Oct 2015 07:00:00 GMT", now),
+            Some(Duration::ZERO)
+        );
+    }
+
+    #[test]
+    fn retry_after_invalid() {
+        assert_eq!(Retry::retry_after("soon", SystemTime::now()), None);
+    }
+}
@@ -0,0 +1,72 @@
+//! Module defining a local HTTP server, so link checks are tested without network access.
+
+use std::io::{BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream};
+use std::sync::{Arc, Mutex};
+use std::thread;
+
+/// Function answering a request line, such as `HEAD /page HTTP/1.1`,
+/// with a status line and headers, such as `503 Service Unavailable\r\nRetry-After: 0`
+type Respond = dyn Fn(&str) -> String + Send + Sync;
+
+/// Represents a local HTTP server running in the background
+pub struct Server {
+    /// Address the server listens to
+    addr: SocketAddr,
+    /// Request lines received so far, in order
+    requests: Arc<Mutex<Vec<String>>>,
+}
+
+impl Server {
+    /// Starts a server answering every request with `respond`, every connection on its own thread
+    pub fn start(respond: impl Fn(&str) -> String + Send + Sync + 'static) -> Self {
+        let listener = TcpListener::bind("127.0.0.1:0").expect("Test server should bind");
+        let addr = listener
+            .local_addr()
+            .expect("Test server should have an address");
+        let requests = Arc::new(Mutex::new(Vec::new()));
+        let respond: Arc<Respond> = Arc::new(respond);
+
+        let log = requests.clone();
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                let respond = respond.clone();
+                let log = log.clone();
+
+                thread::spawn(move || Self::serve(stream, &*respond, &log));
+            }
+        });
+
+        Self { addr, requests }
+    }
+
+    /// URL of `path` on this server
+    pub fn url(&self, path: &str) -> String {
+        format!("http://{}{}", self.addr, path)
+    }
+
+    /// Request lines received so far, in order
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// Answers the single request sent on `stream`, then closes it
+    fn serve(mut stream: TcpStream, respond: &Respond, log: &Mutex<Vec<String>>) {
+        let mut lines = BufReader::new(&stream).lines().map_while(Result::ok);
+        let request = lines.next().unwrap_or_default();
+
+        for header in lines.by_ref() {
+            if header.is_empty() {
+                break;
+            }
+        }
+
+        log.lock().unwrap().push(request.clone());
+
+        let response = format!(
+            "HTTP/1.1 {}\r\nContent-Length: 0\r\nConnection: close\r\n\r\n",
+            respond(&request)
+        );
+        let _ = stream.write_all(response.as_bytes());
+    }
+}
@@ -3,27 +3,50 @@
 
 //! Entry point module, contains the root application
 
+#[cfg(feature = "cli")]
 use std::process::ExitCode;
 
-use ansi_term::Color::{Green, Red};
-
+pub use crate::checker::{Checker, CheckerBuilder, CheckerConfig, ConfigError};
+#[cfg(feature = "cli")]
 pub use crate::cli::Cli;
 pub use crate::files::file::File;
-use crate::files::links::link::LinkStatus;
+pub use crate::files::links::accept::Codes;
+pub use crate::files::links::extractor::{
+    Extractor, Extractors, HtmlExtractor, MarkdownExtractor, PlainExtractor,
+};
+pub use crate::files::links::link::{Failure, Link, LinkStatus};
+pub use crate::files::links::location::Location;
+pub use crate::files::links::method::Method;
+pub use crate::files::links::reason::Reason;
+pub use crate::files::links::results::{Entry, Results};
 pub use crate::files::Files;
+#[cfg(feature = "cli")]
+pub use crate::report::github::GithubReporter;
+pub use crate::report::json::JsonReporter;
+pub use crate::report::junit::JunitReporter;
+pub use crate::report::sarif::SarifReporter;
+#[cfg(feature = "cli")]
+pub use crate::report::text::TextReporter;
+pub use crate::report::{Format, Reporter, Silent};
 
+mod checker;
+#[cfg(feature = "cli")]
 mod cli;
 mod files;
+mod report;
 
 /// Represents the application
+#[cfg(feature = "cli")]
 pub struct App;
 
+#[cfg(feature = "cli")]
 impl Default for App {
     fn default() -> Self {
         Self::new()
     }
 }
 
+#[cfg(feature = "cli")]
 impl App {
     /// Creates a new app
     pub fn new() -> Self {
@@ -38,26 +61,25 @@ impl App {
             return ExitCode::SUCCESS;
         }
 
-        match self.check(cli) {
-            Ok(_) => {
-                println!("\n{}", Green.paint("No dead links !"));
-                ExitCode::SUCCESS
-            }
-            Err(dead_links) => {
-                println!(
-                    "\n{}",
-                    Red.paint(format!("Found {} dead links", dead_links))
-                );
-                ExitCode::FAILURE
-            }
+        let results = cli
+            .checker
+            .check(cli.checker.config().format.reporter().as_mut());
+
+        if Self::status(&results).is_ok() {
+            ExitCode::SUCCESS
+        } else {
+            ExitCode::FAILURE
         }
     }
 
-    /// Checks for dead links. Fails if at least one link is considered dead.
+    /// Checks for dead links, without printing anything. Fails if at least one link is considered dead.
     pub fn check(&self, cli: &Cli) -> Result<(), usize> {
-        let results = Files::new().check(cli);
+        Self::status(&cli.checker.results())
+    }
 
-        let dead_links = results.count_with(LinkStatus::Dead(String::new()));
+    /// Fails with the number of dead links if there is at least one
+    fn status(results: &Results) -> Result<(), usize> {
+        let dead_links = results.count_with(LinkStatus::Dead(Failure::default()));
 
         if dead_links == 0 {
             Ok(())
@@ -68,10 +90,10 @@ impl App {
 
     /// List files and links if asked
     pub fn list(&self, cli: &Cli) {
-        for file in Files::find(cli) {
-            println!("{}", file);
+        for file in cli.checker.files() {
+            println!("{}", file.paint());
             if cli.dry {
-                file.print_links(cli);
+                file.print_links(cli.checker.config());
             }
         }
     }
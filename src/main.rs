@@ -6,6 +6,8 @@ use human_panic::setup_panic;
 
 use deadlinkrs::{App, Cli};
 
+// `setup_panic!` still relies on the deprecated `PanicInfo`
+#[allow(deprecated)]
 fn main() -> ExitCode {
     setup_panic!();
 
@@ -0,0 +1,134 @@
+//! Module for reporting check results in the different output formats.
+//!
+//! Checking only returns data, progress is streamed to a [`Reporter`].
+
+use std::borrow::Cow;
+use std::env;
+use std::path::{Component, Path};
+
+#[cfg(feature = "cli")]
+use ansi_term::Color::Yellow;
+
+use crate::files::file::File;
+use crate::files::links::link::{Link, LinkStatus};
+use crate::files::links::results::Results;
+#[cfg(feature = "cli")]
+use crate::report::github::GithubReporter;
+#[cfg(feature = "cli")]
+use crate::report::json::JsonReporter;
+#[cfg(feature = "cli")]
+use crate::report::junit::JunitReporter;
+#[cfg(feature = "cli")]
+use crate::report::sarif::SarifReporter;
+#[cfg(feature = "cli")]
+use crate::report::text::TextReporter;
+
+#[cfg(feature = "cli")]
+pub mod github;
+pub mod json;
+pub mod junit;
+pub mod sarif;
+#[cfg(feature = "cli")]
+pub mod text;
+
+/// Observes the progress of a check.
+///
+/// Every method does nothing by default.
+pub trait Reporter {
+    /// Called as soon as `link` is requested, with its `status`.
+    ///
+    /// Links found several times are only requested once, links not requested are only reported by [`Reporter::file`].
+    fn link(&mut self, _link: &Link, _status: &LinkStatus) {}
+
+    /// Called as soon as every link of `file` is checked, with their `results`.
+    ///
+    /// Files are reported in order.
+    fn file(&mut self, _file: &File, _results: &Results) {}
+
+    /// Called when something went wrong without stopping the check, such as an unreadable cache
+    fn warning(&mut self, _message: &str) {}
+
+    /// Called once every link is checked, with the merged `results` of every file
+    fn finish(&mut self, _results: &Results) {}
+}
+
+/// Reporter ignoring everything, for library use
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Silent;
+
+impl Reporter for Silent {}
+
+/// Prints a non fatal `message` on stderr
+fn print_warning(message: &str) {
+    #[cfg(feature = "cli")]
+    eprintln!("{}", Yellow.paint(message));
+    #[cfg(not(feature = "cli"))]
+    eprintln!("{}", message);
+}
+
+/// Components of the relative `path`, as written in reports.
+///
+/// `.` components are left out, so `./docs/index.md` is written `docs/index.md`.
+fn components(path: &Path) -> impl Iterator<Item = Cow<'_, str>> {
+    path.components().filter_map(|component| match component {
+        Component::ParentDir => Some(Cow::Borrowed("..")),
+        Component::Normal(name) => Some(name.to_string_lossy()),
+        _ => None,
+    })
+}
+
+/// Represents the output format of the results
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Format {
+    /// Colored text, printed file by file while checking
+    #[default]
+    Text,
+    /// A single JSON document, printed once every link is checked
+    Json,
+    /// A JUnit XML report, printed once every link is checked
+    Junit,
+    /// A SARIF log for code scanning, printed once every link is checked
+    Sarif,
+    /// Colored text, followed by GitHub Actions annotations once every link is checked
+    Github,
+}
+
+impl Format {
+    /// Names of every format, as accepted by cli arguments
+    pub const NAMES: [&'static str; 5] = ["text", "json", "junit", "sarif", "github"];
+
+    /// Finds the format named `name`, one of [`Format::NAMES`]
+    pub fn from_name(name: &str) -> Option<Self> {
+        match name {
+            "text" => Some(Format::Text),
+            "json" => Some(Format::Json),
+            "junit" => Some(Format::Junit),
+            "sarif" => Some(Format::Sarif),
+            "github" => Some(Format::Github),
+            _ => None,
+        }
+    }
+
+    /// Detects the format suiting the environment.
+    ///
+    /// Inside GitHub Actions, annotations are emitted. Otherwise, text is printed.
+    pub fn detect() -> Self {
+        if env::var("GITHUB_ACTIONS").map_or(false, |v| v == "true") {
+            Format::Github
+        } else {
+            Format::Text
+        }
+    }
+
+    /// Creates the reporter printing results in this format
+    #[cfg(feature = "cli")]
+    pub fn reporter(&self) -> Box<dyn Reporter> {
+        match self {
+            Format::Text => Box::new(TextReporter::new()),
+            Format::Json => Box::new(JsonReporter),
+            Format::Junit => Box::new(JunitReporter),
+            Format::Sarif => Box::new(SarifReporter),
+            Format::Github => Box::new(GithubReporter::new()),
+        }
+    }
+}
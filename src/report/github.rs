@@ -0,0 +1,141 @@
+//! GitHub Actions report of the check results.
+//!
+//! Dead links and warnings are emitted as workflow command annotations,
+//! so they show inline on pull request diffs.
+
+use std::path::Path;
+
+use crate::files::file::File;
+use crate::files::links::link::LinkStatus;
+use crate::files::links::results::Results;
+use crate::report::text::TextReporter;
+use crate::report::{components, Reporter};
+
+/// Reporter printing colored results like [`TextReporter`], followed by annotations
+#[derive(Debug, Clone, Copy)]
+pub struct GithubReporter {
+    /// Reporter printing the colored results
+    text: TextReporter,
+}
+
+impl Default for GithubReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl GithubReporter {
+    /// Creates a new GitHub Actions reporter
+    pub fn new() -> Self {
+        Self {
+            text: TextReporter::new(),
+        }
+    }
+}
+
+impl Reporter for GithubReporter {
+    fn file(&mut self, file: &File, results: &Results) {
+        self.text.file(file, results);
+    }
+
+    fn warning(&mut self, message: &str) {
+        self.text.warning(message);
+    }
+
+    fn finish(&mut self, results: &Results) {
+        print!("{}", render(results));
+        self.text.finish(results);
+    }
+}
+
+/// Renders `results` as GitHub Actions annotations, one per line
+pub fn render(results: &Results) -> String {
+    results
+        .iter()
+        .filter_map(|(link, status, location)| {
+            let (command, title, failure) = match status {
+                LinkStatus::Dead(failure) => ("error", "Dead link", failure),
+                LinkStatus::Warn(failure) => ("warning", "Link warning", failure),
+                _ => return None,
+            };
+
+            Some(format!(
+                "::{} file={},line={},col={},title={}::{}\n",
+                command,
+                escape_property(&file(&location.file)),
+                location.line,
+                location.column,
+                title,
+                escape_data(&format!("{} {}", link.as_str(), failure))
+            ))
+        })
+        .collect()
+}
+
+/// Path of the file at `path` in annotations, relative to the repository when `path` is relative
+fn file(path: &Path) -> String {
+    if path.is_absolute() {
+        return path.display().to_string();
+    }
+
+    components(path).collect::<Vec<_>>().join("/")
+}
+
+/// Escapes the message of a workflow command
+fn escape_data(s: &str) -> String {
+    s.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+/// Escapes a property value of a workflow command
+fn escape_property(s: &str) -> String {
+    escape_data(s).replace(':', "%3A").replace(',', "%2C")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http::StatusCode;
+
+    use crate::files::links::link::{Failure, Link};
+    use crate::files::links::location::Location;
+    use crate::files::links::reason::Reason;
+
+    use super::*;
+
+    #[test]
+    fn render_results() {
+        let mut results = Results::new();
+        let location = Location {
+            file: PathBuf::from("./docs/a,b.md"),
+            span: 0..0,
+            line: 12,
+            column: 5,
+            ..Location::default()
+        };
+
+        results.inserts(
+            &Link::new(&"https://example.com".to_string()),
+            &location,
+            LinkStatus::Alive(1),
+        );
+        results.inserts(
+            &Link::new(&"https://example.com/missing".to_string()),
+            &location,
+            LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1)),
+        );
+        results.inserts(
+            &Link::new(&"https://example.com/100%".to_string()),
+            &location,
+            LinkStatus::Warn(Failure::new(Reason::Timeout, 2)),
+        );
+
+        assert_eq!(
+            render(&results),
+            "::error file=docs/a%2Cb.md,line=12,col=5,title=Dead link::https://example.com/missing 404 Not Found\n\
+             ::warning file=docs/a%2Cb.md,line=12,col=5,title=Link warning::https://example.com/100%25 Timed out (after 2 attempts)\n"
+        );
+    }
+}
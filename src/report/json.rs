@@ -0,0 +1,274 @@
+//! JSON report of the check results.
+//!
+//! The report is a single object, its schema is stable:
+//!
+//! ```json
+//! {
+//!   "version": 1,
+//!   "links": [
+//!     {
+//!       "file": "docs/index.md",
+//!       "url": "https://example.com/missing",
+//!       "line": 12,
+//!       "column": 5,
+//!       "status": "dead",
+//!       "cached": false,
+//!       "reason": "status",
+//!       "message": "404 Not Found",
+//!       "http_code": 404,
+//!       "attempts": 1
+//!     }
+//!   ],
+//!   "summary": {
+//!     "files": 1,
+//!     "links": 1,
+//!     "alive": 0,
+//!     "dead": 1,
+//!     "warn": 0,
+//!     "ignored": 0,
+//!     "cached": 0
+//!   }
+//! }
+//! ```
+//!
+//! - `status` is one of `alive`, `dead`, `warn` or `ignored`.
+//! - `cached` is true when the link status was reused from the cache.
+//! - `reason` and `message` are `null` unless the link is `dead` or `warn`.
+//! - `attempts` is the number of requests sent, `null` for ignored links and alive links from the cache.
+//!   `reason` is one of the reasons accepted by `--dead-on` and `--warn-on`.
+//! - `http_code` is `null` unless the reason is `status`.
+//! - `line` and `column` start at 1, `column` counts characters.
+
+use std::collections::HashSet;
+
+use serde::Serialize;
+
+use crate::files::links::link::LinkStatus;
+use crate::files::links::reason::Reason;
+use crate::files::links::results::Results;
+use crate::report::{print_warning, Reporter};
+
+/// Version of the report schema, incremented on breaking changes
+const VERSION: u32 = 1;
+
+/// The whole report
+#[derive(Serialize)]
+struct Report<'a> {
+    /// Version of the report schema
+    version: u32,
+    /// Every link checked, in order
+    links: Vec<Entry<'a>>,
+    /// Counts of the results
+    summary: Summary,
+}
+
+/// A single link
+#[derive(Serialize)]
+struct Entry<'a> {
+    /// Path to the file containing the link
+    file: String,
+    /// URL of the link
+    url: &'a str,
+    /// Line of the link
+    line: usize,
+    /// Column of the link
+    column: usize,
+    /// Status of the link
+    status: &'static str,
+    /// The status was reused from the cache
+    cached: bool,
+    /// Name of the failure reason
+    reason: Option<&'static str>,
+    /// Human readable failure reason
+    message: Option<String>,
+    /// HTTP status code returned by the server
+    http_code: Option<u16>,
+    /// Number of requests sent to get the final status
+    attempts: Option<u32>,
+}
+
+/// Counts of the results
+#[derive(Serialize)]
+struct Summary {
+    /// Number of files containing links
+    files: usize,
+    /// Number of links
+    links: usize,
+    /// Number of alive links
+    alive: usize,
+    /// Number of dead links
+    dead: usize,
+    /// Number of links with a warning
+    warn: usize,
+    /// Number of ignored links
+    ignored: usize,
+    /// Number of links whose status was reused from the cache
+    cached: usize,
+}
+
+/// Reporter printing a JSON document once every link is checked
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonReporter;
+
+impl Reporter for JsonReporter {
+    fn warning(&mut self, message: &str) {
+        print_warning(message);
+    }
+
+    fn finish(&mut self, results: &Results) {
+        println!("{}", render(results));
+    }
+}
+
+/// Renders `results` as a JSON report
+pub fn render(results: &Results) -> String {
+    let links: Vec<Entry> = results
+        .iter()
+        .map(|(link, status, location)| {
+            let attempts = match status {
+                LinkStatus::Alive(attempts) => Some(*attempts),
+                LinkStatus::Dead(failure) | LinkStatus::Warn(failure) => Some(failure.attempts),
+                LinkStatus::Cached | LinkStatus::Ignored => None,
+            };
+            let (status, cached, failure) = match status {
+                LinkStatus::Alive(_) => ("alive", false, None),
+                LinkStatus::Cached => ("alive", true, None),
+                LinkStatus::Dead(failure) => ("dead", failure.cached, Some(failure)),
+                LinkStatus::Warn(failure) => ("warn", failure.cached, Some(failure)),
+                LinkStatus::Ignored => ("ignored", false, None),
+            };
+
+            Entry {
+                file: location.file.display().to_string(),
+                url: link.as_str(),
+                line: location.line,
+                column: location.column,
+                status,
+                cached,
+                reason: failure.map(|f| f.reason.name()),
+                message: failure.map(|f| f.reason.to_string()),
+                http_code: failure.and_then(|f| match f.reason {
+                    Reason::Status(code) => Some(code.as_u16()),
+                    _ => None,
+                }),
+                attempts,
+            }
+        })
+        .collect();
+
+    let count = |status: &str| links.iter().filter(|l| l.status == status).count();
+
+    let summary = Summary {
+        files: links.iter().map(|l| &l.file).collect::<HashSet<_>>().len(),
+        links: links.len(),
+        alive: count("alive"),
+        dead: count("dead"),
+        warn: count("warn"),
+        ignored: count("ignored"),
+        cached: links.iter().filter(|l| l.cached).count(),
+    };
+
+    serde_json::to_string_pretty(&Report {
+        version: VERSION,
+        links,
+        summary,
+    })
+    .expect("Report should be serializable")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http::StatusCode;
+    use serde_json::{json, Value};
+
+    use crate::files::links::link::{Failure, Link};
+    use crate::files::links::location::Location;
+
+    use super::*;
+
+    #[test]
+    fn render_results() {
+        let mut results = Results::new();
+        let location = Location {
+            file: PathBuf::from("docs/index.md"),
+            span: 0..0,
+            line: 12,
+            column: 5,
+            ..Location::default()
+        };
+
+        results.inserts(
+            &Link::new(&"https://example.com/missing".to_string()),
+            &location,
+            LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1)),
+        );
+        results.inserts(
+            &Link::new(&"https://example.com".to_string()),
+            &location,
+            LinkStatus::Cached,
+        );
+        results.inserts(
+            &Link::new(&"https://example.com/flaky".to_string()),
+            &location,
+            LinkStatus::Alive(3),
+        );
+
+        let report: Value = serde_json::from_str(&render(&results)).unwrap();
+
+        assert_eq!(
+            report,
+            json!({
+                "version": 1,
+                "links": [
+                    {
+                        "file": "docs/index.md",
+                        "url": "https://example.com/missing",
+                        "line": 12,
+                        "column": 5,
+                        "status": "dead",
+                        "cached": false,
+                        "reason": "status",
+                        "message": "404 Not Found",
+                        "http_code": 404,
+                        "attempts": 1
+                    },
+                    {
+                        "file": "docs/index.md",
+                        "url": "https://example.com",
+                        "line": 12,
+                        "column": 5,
+                        "status": "alive",
+                        "cached": true,
+                        "reason": null,
+                        "message": null,
+                        "http_code": null,
+                        "attempts": null
+                    },
+                    {
+                        "file": "docs/index.md",
+                        "url": "https://example.com/flaky",
+                        "line": 12,
+                        "column": 5,
+                        "status": "alive",
+                        "cached": false,
+                        "reason": null,
+                        "message": null,
+                        "http_code": null,
+                        "attempts": 3
+                    }
+                ],
+                "summary": {
+                    "files": 1,
+                    "links": 3,
+                    "alive": 2,
+                    "dead": 1,
+                    "warn": 0,
+                    "ignored": 0,
+                    "cached": 1
+                }
+            })
+        );
+    }
+}
@@ -0,0 +1,202 @@
+//! JUnit XML report of the check results.
+//!
+//! Every file is a test suite and every checked link a test case named after its URL.
+//! Dead links are failures, links with a warning are skipped and detail the warning in `system-out`.
+//! Ignored links are not checked, so they are left out.
+
+use std::fmt::Write;
+use std::path::Path;
+
+use crate::files::links::link::LinkStatus;
+use crate::files::links::results::{Entry, Results};
+use crate::report::{print_warning, Reporter};
+
+/// Reporter printing a JUnit XML report once every link is checked
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JunitReporter;
+
+impl Reporter for JunitReporter {
+    fn warning(&mut self, message: &str) {
+        print_warning(message);
+    }
+
+    fn finish(&mut self, results: &Results) {
+        print!("{}", render(results));
+    }
+}
+
+/// Renders `results` as a JUnit XML report
+pub fn render(results: &Results) -> String {
+    let suites: Vec<(&Path, Vec<Entry>)> = results
+        .by_file()
+        .into_iter()
+        .map(|(file, mut cases)| {
+            cases.retain(|(_, status, _)| **status != LinkStatus::Ignored);
+            (file, cases)
+        })
+        .filter(|(_, cases)| !cases.is_empty())
+        .collect();
+
+    let cases = || suites.iter().flat_map(|(_, cases)| cases);
+
+    let mut xml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    let _ = writeln!(
+        xml,
+        "<testsuites name=\"deadlinkrs\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">",
+        cases().count(),
+        cases()
+            .filter(|(_, s, _)| matches!(s, LinkStatus::Dead(_)))
+            .count(),
+        cases()
+            .filter(|(_, s, _)| matches!(s, LinkStatus::Warn(_)))
+            .count(),
+    );
+
+    for (file, cases) in &suites {
+        suite(&mut xml, file, cases);
+    }
+
+    xml.push_str("</testsuites>\n");
+    xml
+}
+
+/// Writes the test suite of a single file
+fn suite(xml: &mut String, file: &Path, cases: &[Entry]) {
+    let file = escape(&file.display().to_string());
+
+    let _ = writeln!(
+        xml,
+        "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" skipped=\"{}\">",
+        file,
+        cases.len(),
+        cases
+            .iter()
+            .filter(|(_, s, _)| matches!(s, LinkStatus::Dead(_)))
+            .count(),
+        cases
+            .iter()
+            .filter(|(_, s, _)| matches!(s, LinkStatus::Warn(_)))
+            .count(),
+    );
+
+    for (link, status, location) in cases {
+        let _ = write!(
+            xml,
+            "    <testcase name=\"{}\" classname=\"{}\" file=\"{}\" line=\"{}\"",
+            escape(link.as_str()),
+            file,
+            file,
+            location.line
+        );
+
+        match status {
+            LinkStatus::Dead(failure) => {
+                let message = escape(&failure.to_string());
+                let _ = writeln!(
+                    xml,
+                    ">\n      <failure message=\"{}\" type=\"{}\">{}:{}:{} {}</failure>\n    </testcase>",
+                    message,
+                    failure.reason.name(),
+                    file,
+                    location.line,
+                    location.column,
+                    message
+                );
+            }
+            LinkStatus::Warn(failure) => {
+                let message = escape(&failure.to_string());
+                let _ = writeln!(
+                    xml,
+                    ">\n      <skipped message=\"{}\"/>\n      <system-out>{}</system-out>\n    </testcase>",
+                    message, message
+                );
+            }
+            _ => xml.push_str("/>\n"),
+        }
+    }
+
+    xml.push_str("  </testsuite>\n");
+}
+
+/// Escapes `s` for use in XML text and attributes
+fn escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+        .replace('\'', "&apos;")
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http::StatusCode;
+
+    use crate::files::links::link::{Failure, Link};
+    use crate::files::links::location::Location;
+    use crate::files::links::reason::Reason;
+
+    use super::*;
+
+    fn location(file: &str) -> Location {
+        Location {
+            file: PathBuf::from(file),
+            span: 0..0,
+            line: 3,
+            column: 7,
+            ..Location::default()
+        }
+    }
+
+    #[test]
+    fn escape_xml() {
+        assert_eq!(
+            escape("https://example.com/?a=1&b=\"<2>\""),
+            "https://example.com/?a=1&amp;b=&quot;&lt;2&gt;&quot;"
+        );
+    }
+
+    #[test]
+    fn render_results() {
+        let mut results = Results::new();
+        let link = |url: &str| Link::new(&url.to_string());
+
+        results.inserts(
+            &link("https://example.com"),
+            &location("a.md"),
+            LinkStatus::Alive(1),
+        );
+        results.inserts(
+            &link("https://example.com/missing"),
+            &location("a.md"),
+            LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1)),
+        );
+        results.inserts(
+            &link("https://example.invalid"),
+            &location("b.md"),
+            LinkStatus::Warn(Failure::new(Reason::Dns, 1)),
+        );
+        results.inserts(&link("tel:0123"), &location("b.md"), LinkStatus::Ignored);
+
+        assert_eq!(
+            render(&results),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="deadlinkrs" tests="3" failures="1" skipped="1">
+  <testsuite name="a.md" tests="2" failures="1" skipped="0">
+    <testcase name="https://example.com" classname="a.md" file="a.md" line="3"/>
+    <testcase name="https://example.com/missing" classname="a.md" file="a.md" line="3">
+      <failure message="404 Not Found" type="status">a.md:3:7 404 Not Found</failure>
+    </testcase>
+  </testsuite>
+  <testsuite name="b.md" tests="1" failures="0" skipped="1">
+    <testcase name="https://example.invalid" classname="b.md" file="b.md" line="3">
+      <skipped message="Host not found"/>
+      <system-out>Host not found</system-out>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}
@@ -0,0 +1,224 @@
+//! SARIF report of the check results, for code scanning integrations.
+//!
+//! Every dead link or link with a warning is a result, identified by the rule of its failure reason.
+//! Alive and ignored links are left out.
+//! Columns are counted in Unicode code points, as declared by the `columnKind` of the run.
+
+use std::path::Path;
+
+use serde_json::{json, Value};
+use url::Url;
+
+use crate::files::links::link::LinkStatus;
+use crate::files::links::reason::Reason;
+use crate::files::links::results::Results;
+use crate::report::{components, print_warning, Reporter};
+
+/// Version of the SARIF specification followed
+const VERSION: &str = "2.1.0";
+
+/// JSON schema of the SARIF specification followed
+const SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+
+/// Reporter printing a SARIF log once every link is checked
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SarifReporter;
+
+impl Reporter for SarifReporter {
+    fn warning(&mut self, message: &str) {
+        print_warning(message);
+    }
+
+    fn finish(&mut self, results: &Results) {
+        println!("{}", render(results));
+    }
+}
+
+/// Renders `results` as a SARIF log
+pub fn render(results: &Results) -> String {
+    let results: Vec<Value> = results
+        .iter()
+        .filter_map(|(link, status, location)| {
+            let (level, failure) = match status {
+                LinkStatus::Dead(failure) => ("error", failure),
+                LinkStatus::Warn(failure) => ("warning", failure),
+                _ => return None,
+            };
+
+            Some(json!({
+                "ruleId": failure.reason.name(),
+                "level": level,
+                "message": {
+                    "text": format!("Link {} is not alive: {}", link.as_str(), failure),
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": uri(&location.file),
+                        },
+                        "region": {
+                            "startLine": location.line,
+                            "startColumn": location.column,
+                            "endLine": location.end_line,
+                            "endColumn": location.end_column,
+                        },
+                    },
+                }],
+            }))
+        })
+        .collect();
+
+    let rules: Vec<Value> = Reason::NAMES
+        .iter()
+        .map(|name| {
+            json!({
+                "id": name,
+                "shortDescription": {
+                    "text": description(name),
+                },
+            })
+        })
+        .collect();
+
+    serde_json::to_string_pretty(&json!({
+        "version": VERSION,
+        "$schema": SCHEMA,
+        "runs": [{
+            "tool": {
+                "driver": {
+                    "name": env!("CARGO_PKG_NAME"),
+                    "version": env!("CARGO_PKG_VERSION"),
+                    "informationUri": env!("CARGO_PKG_HOMEPAGE"),
+                    "rules": rules,
+                },
+            },
+            "columnKind": "unicodeCodePoints",
+            "results": results,
+        }],
+    }))
+    .expect("SARIF log should be serializable")
+}
+
+/// Percent-encoded URI of the file at `path`, relative unless the path is absolute
+fn uri(path: &Path) -> String {
+    if path.is_absolute() {
+        if let Ok(url) = Url::from_file_path(path) {
+            return url.to_string();
+        }
+    }
+
+    components(path)
+        .map(|name| match name.as_ref() {
+            ".." => String::from(".."),
+            name => segment(name),
+        })
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+/// Percent-encodes `name`, a single segment of a path
+fn segment(name: &str) -> String {
+    let mut url = Url::parse("file:///").expect("file URL should be valid");
+    url.path_segments_mut()
+        .expect("file URL should have a path")
+        .push(name);
+
+    url.path()[1..].to_string()
+}
+
+/// Describes the rule of the reason named `name`
+fn description(name: &str) -> &'static str {
+    match name {
+        "status" => "Link answered with a status code that is not accepted",
+        "dns" => "Link host could not be resolved",
+        "connect" => "Connection to the link host failed",
+        "tls" => "TLS handshake with the link host failed",
+        "timeout" => "Link took too long to answer",
+        "redirect" => "Link redirected too many times",
+        "invalid-url" => "Link is not a valid URL",
+        "body" => "Link response body could not be read",
+        "missing" => "Local link points to a file that does not exist",
+        _ => "Link is not alive",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use http::StatusCode;
+
+    use crate::files::links::link::{Failure, Link};
+    use crate::files::links::location::Location;
+
+    use super::*;
+
+    #[test]
+    fn render_results() {
+        let mut results = Results::new();
+        let location = Location {
+            file: PathBuf::from("./docs/my file.md"),
+            span: 0..0,
+            line: 12,
+            column: 5,
+            end_line: 12,
+            end_column: 32,
+        };
+
+        results.inserts(
+            &Link::new(&"https://example.com".to_string()),
+            &location,
+            LinkStatus::Alive(1),
+        );
+        results.inserts(
+            &Link::new(&"https://example.com/missing".to_string()),
+            &location,
+            LinkStatus::Dead(Failure::new(Reason::Status(StatusCode::NOT_FOUND), 1)),
+        );
+
+        let log: Value = serde_json::from_str(&render(&results)).unwrap();
+        let run = &log["runs"][0];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(run["columnKind"], "unicodeCodePoints");
+        assert_eq!(
+            run["tool"]["driver"]["rules"].as_array().unwrap().len(),
+            Reason::NAMES.len()
+        );
+        assert_eq!(
+            run["results"],
+            json!([{
+                "ruleId": "status",
+                "level": "error",
+                "message": {
+                    "text": "Link https://example.com/missing is not alive: 404 Not Found",
+                },
+                "locations": [{
+                    "physicalLocation": {
+                        "artifactLocation": {
+                            "uri": "docs/my%20file.md",
+                        },
+                        "region": {
+                            "startLine": 12,
+                            "startColumn": 5,
+                            "endLine": 12,
+                            "endColumn": 32,
+                        },
+                    },
+                }],
+            }])
+        );
+    }
+
+    #[test]
+    fn uris() {
+        assert_eq!(uri(Path::new("./docs/my file.md")), "docs/my%20file.md");
+        assert_eq!(
+            uri(Path::new("../100%/a#b?c/é.md")),
+            "../100%25/a%23b%3Fc/%C3%A9.md"
+        );
+        if cfg!(unix) {
+            assert_eq!(uri(Path::new("/tmp/a b.md")), "file:///tmp/a%20b.md");
+        }
+    }
+}
@@ -0,0 +1,115 @@
+//! Colored text report of the check results.
+//!
+//! Results are printed file by file while checking, followed by a summary.
+
+use ansi_term::Color::{Green, Red};
+
+use crate::files::file::File;
+use crate::files::links::link::LinkStatus::{Alive, Cached, Dead, Ignored, Warn};
+use crate::files::links::link::{Failure, LinkStatus};
+use crate::files::links::results::Results;
+use crate::report::{print_warning, Reporter};
+
+/// Reporter printing colored results on the terminal
+#[derive(Debug, Clone, Copy)]
+pub struct TextReporter;
+
+impl Default for TextReporter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl TextReporter {
+    /// Creates a new text reporter
+    pub fn new() -> Self {
+        Self {}
+    }
+}
+
+impl Reporter for TextReporter {
+    fn file(&mut self, file: &File, results: &Results) {
+        println!("{}{}", file.paint(), render(results));
+    }
+
+    fn warning(&mut self, message: &str) {
+        print_warning(message);
+    }
+
+    fn finish(&mut self, results: &Results) {
+        println!("\n{}", summary(results));
+    }
+}
+
+/// Renders `results` as colored text, one link per line
+pub fn render(results: &Results) -> String {
+    results
+        .iter()
+        .map(|(link, status, location)| {
+            let line = match status {
+                Alive(attempts) if *attempts > 1 => {
+                    link.ok(location, &format!("(after {} attempts)", attempts))
+                }
+                Alive(_) => link.ok(location, ""),
+                Dead(r) => link.err(location, &r.to_string()),
+                Warn(r) => link.warn(location, &r.to_string()),
+                Cached => link.cache(location, ""),
+                Ignored => link.ignored(location, ""),
+            };
+
+            format!("\n{}", line)
+        })
+        .collect()
+}
+
+/// Renders the colored summary of `results`
+pub fn summary(results: &Results) -> String {
+    match results.count_with(LinkStatus::Dead(Failure::default())) {
+        0 => Green.paint("No dead links !").to_string(),
+        dead_links => Red
+            .paint(format!("Found {} dead links", dead_links))
+            .to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::files::links::link::Link;
+    use crate::files::links::location::Location;
+    use crate::files::links::reason::Reason;
+
+    use super::*;
+
+    #[test]
+    fn summary_counts_dead_links() {
+        let mut results = Results::new();
+        let link = Link::new(&"https://example.com".to_string());
+
+        assert!(summary(&results).contains("No dead links !"));
+
+        results.inserts(
+            &link,
+            &Location::default(),
+            LinkStatus::Dead(Failure::new(Reason::Dns, 1)),
+        );
+        results.inserts(
+            &link,
+            &Location::default(),
+            LinkStatus::Warn(Failure::new(Reason::Dns, 1)),
+        );
+
+        assert!(summary(&results).contains("Found 1 dead links"));
+    }
+
+    #[test]
+    fn render_attempts() {
+        let mut results = Results::new();
+        let link = Link::new(&"https://example.com".to_string());
+
+        results.inserts(&link, &Location::default(), Alive(1));
+        assert!(!render(&results).contains("attempts"));
+
+        results.inserts(&link, &Location::default(), Alive(3));
+        assert!(render(&results).contains("(after 3 attempts)"));
+    }
+}
@@ -1,19 +1,13 @@
-use globset::{Glob, GlobSetBuilder};
-
 use crate::common::TestData;
-use deadlinkrs::{Cli, File, Files};
+use deadlinkrs::{Checker, File};
 
 mod common;
 
 #[test]
 fn list() {
-    let cli = Cli {
-        path: vec![String::from("tests_data")],
-        list: true,
-        ..Cli::default()
-    };
+    let checker = Checker::builder().path("tests_data").build().unwrap();
 
-    let mut res: Vec<File> = Files::find(&cli).collect();
+    let mut res: Vec<File> = checker.files().collect();
     res.sort();
 
     assert_eq!(res, TestData::all());
@@ -21,14 +15,13 @@ fn list() {
 
 #[test]
 fn list_hidden() {
-    let cli = Cli {
-        path: vec![String::from("tests_data/hidden")],
-        hidden: true,
-        list: true,
-        ..Cli::default()
-    };
+    let checker = Checker::builder()
+        .path("tests_data/hidden")
+        .hidden(true)
+        .build()
+        .unwrap();
 
-    let mut res: Vec<File> = Files::find(&cli).collect();
+    let mut res: Vec<File> = checker.files().collect();
     res.sort();
 
     assert_eq!(res, TestData::hidden());
@@ -36,17 +29,13 @@ fn list_hidden() {
 
 #[test]
 fn list_html() {
-    let cli = Cli {
-        path: vec![String::from("tests_data")],
-        glob: GlobSetBuilder::new()
-            .add(Glob::new("**/*.html").unwrap())
-            .build()
-            .unwrap(),
-        list: true,
-        ..Cli::default()
-    };
+    let checker = Checker::builder()
+        .path("tests_data")
+        .glob("**/*.html")
+        .build()
+        .unwrap();
 
-    let mut res: Vec<File> = Files::find(&cli).collect();
+    let mut res: Vec<File> = checker.files().collect();
     res.sort();
 
     assert_eq!(res, TestData::html());
@@ -54,18 +43,14 @@ fn list_html() {
 
 #[test]
 fn list_hidden_html() {
-    let cli = Cli {
-        path: vec![String::from("tests_data")],
-        glob: GlobSetBuilder::new()
-            .add(Glob::new("**/*.html").unwrap())
-            .build()
-            .unwrap(),
-        hidden: true,
-        list: true,
-        ..Cli::default()
-    };
-
-    let mut res: Vec<File> = Files::find(&cli).collect();
+    let checker = Checker::builder()
+        .path("tests_data")
+        .glob("**/*.html")
+        .hidden(true)
+        .build()
+        .unwrap();
+
+    let mut res: Vec<File> = checker.files().collect();
     res.sort();
 
     assert_eq!(res, TestData::hidden_html());